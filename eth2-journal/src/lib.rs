@@ -0,0 +1,327 @@
+//! Public journal committed by the eth2 guests.
+//!
+//! Every eth2 guest (Lean init/noinit and Rust) commits an [`Eth2Journal`] with
+//! `env::commit`; the host decodes it with `journal.decode()`. The full post-state
+//! is not part of the journal: when the input requests it, the guest writes it to
+//! [`POST_STATE_FD`], which the host captures as a private output.
+//!
+//! The aggregation guest reads an [`Eth2AggregateInput`] and commits an
+//! [`Eth2AggregateJournal`] covering the span of the receipts it composes.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub type Root = [u8; 32];
+
+/// Guest file descriptor carrying the optional serialized post-state
+pub const POST_STATE_FD: u32 = 10;
+
+/// Input that makes a `heap-snapshot` build of the Lean init guest initialize Lean, write the
+/// memory regions listed after it (u32 LE address and length pairs) to [`HEAP_SNAPSHOT_FD`]
+/// and stop. As a BeaconState's leading genesis_time it would be absurd, so no real input
+/// starts with it.
+pub const HEAP_SNAPSHOT_REQUEST: &[u8] = b"lean-risc0 heap snapshot";
+
+/// Guest file descriptor carrying heap snapshot records: u32 LE address and length, then the
+/// bytes at that address
+pub const HEAP_SNAPSHOT_FD: u32 = 11;
+
+/// Outcome of the state transition
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Eth2Status {
+    Ok,
+    Error(Eth2Error),
+}
+
+/// Where the transition failed and why; `reason` is the guest's error message
+/// (identical strings in the Lean and Rust STFs), empty for decode failures.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eth2Error {
+    pub stage: Stage,
+    pub reason: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stage {
+    StateDecode,
+    BlockDecode,
+    Slots,
+    BlockSignature,
+    Header,
+    Withdrawals,
+    ExecutionPayload,
+    Randao,
+    Eth1Data,
+    /// `index` is the position within the block's list of that operation kind
+    Operation { kind: OperationKind, index: u32 },
+    SyncAggregate,
+    StateRoot,
+    /// The Lean runtime returned no result
+    Runtime,
+    /// A Lean module initializer failed, so the transition did not run; `reason` names it
+    Init,
+    /// The input asked for a check the guest cannot perform; `reason` names it
+    Unsupported,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationKind {
+    ProposerSlashing,
+    AttesterSlashing,
+    Attestation,
+    Deposit,
+    VoluntaryExit,
+    BlsToExecutionChange,
+}
+
+impl Eth2Error {
+    pub fn new(stage: Stage, reason: &str) -> Self {
+        Eth2Error { stage, reason: reason.into() }
+    }
+}
+
+impl Stage {
+    /// Stage as emitted by the Lean guest: stage code, operation kind code, operation index.
+    /// Code 0 is success and has no stage.
+    pub fn from_codes(stage: u8, kind: u8, index: u32) -> Option<Self> {
+        Some(match stage {
+            1 => Stage::StateDecode,
+            2 => Stage::BlockDecode,
+            3 => Stage::Slots,
+            4 => Stage::BlockSignature,
+            5 => Stage::Header,
+            6 => Stage::Withdrawals,
+            7 => Stage::ExecutionPayload,
+            8 => Stage::Randao,
+            9 => Stage::Eth1Data,
+            10 => Stage::Operation { kind: OperationKind::from_code(kind)?, index },
+            11 => Stage::SyncAggregate,
+            12 => Stage::StateRoot,
+            13 => Stage::Unsupported,
+            _ => return None,
+        })
+    }
+}
+
+impl OperationKind {
+    pub fn from_code(code: u8) -> Option<Self> {
+        Some(match code {
+            0 => OperationKind::ProposerSlashing,
+            1 => OperationKind::AttesterSlashing,
+            2 => OperationKind::Attestation,
+            3 => OperationKind::Deposit,
+            4 => OperationKind::VoluntaryExit,
+            5 => OperationKind::BlsToExecutionChange,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OperationKind::ProposerSlashing => "proposer_slashings",
+            OperationKind::AttesterSlashing => "attester_slashings",
+            OperationKind::Attestation => "attestations",
+            OperationKind::Deposit => "deposits",
+            OperationKind::VoluntaryExit => "voluntary_exits",
+            OperationKind::BlsToExecutionChange => "bls_to_execution_changes",
+        })
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::StateDecode => f.write_str("state decode"),
+            Stage::BlockDecode => f.write_str("block decode"),
+            Stage::Slots => f.write_str("process_slots"),
+            Stage::BlockSignature => f.write_str("block signature"),
+            Stage::Header => f.write_str("block header"),
+            Stage::Withdrawals => f.write_str("withdrawals"),
+            Stage::ExecutionPayload => f.write_str("execution payload"),
+            Stage::Randao => f.write_str("randao"),
+            Stage::Eth1Data => f.write_str("eth1 data"),
+            Stage::Operation { kind, index } => write!(f, "{}[{}]", kind, index),
+            Stage::SyncAggregate => f.write_str("sync aggregate"),
+            Stage::StateRoot => f.write_str("state root"),
+            Stage::Runtime => f.write_str("runtime"),
+            Stage::Init => f.write_str("initialization"),
+            Stage::Unsupported => f.write_str("unsupported"),
+        }
+    }
+}
+
+impl fmt::Display for Eth2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.reason.is_empty() {
+            write!(f, "{} failed", self.stage)
+        } else {
+            write!(f, "{}: {}", self.stage, self.reason)
+        }
+    }
+}
+
+/// Roots are hash_tree_root of the decoded pre-state, a block message and the final
+/// post-state; they stay zero for stages that were not reached.
+///
+/// The input carries a chain of blocks applied in order. `block_root` and `slot` belong
+/// to the last block on success and to the failing block (index `blocks_applied`) on error.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eth2Journal {
+    pub pre_state_root: Root,
+    pub block_root: Root,
+    pub post_state_root: Root,
+    pub slot: u64,
+    pub blocks_applied: u32,
+    pub status: Eth2Status,
+}
+
+impl Eth2Journal {
+    pub fn new(status: Eth2Status) -> Self {
+        Eth2Journal {
+            pre_state_root: [0; 32],
+            block_root: [0; 32],
+            post_state_root: [0; 32],
+            slot: 0,
+            blocks_applied: 0,
+            status,
+        }
+    }
+}
+
+/// Input of the aggregation guest: journals of consecutive eth2 receipts, all proven by
+/// the guest `image_id`. The host attaches the receipts themselves as assumptions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eth2AggregateInput {
+    pub image_id: [u32; 8],
+    pub journals: Vec<Eth2Journal>,
+}
+
+/// Span proven by the aggregation guest: the first receipt's pre-state to the last
+/// receipt's post-state, over `blocks` blocks in `receipts` receipts. Verifiers must
+/// check `image_id` against the eth2 guest they trust.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eth2AggregateJournal {
+    pub image_id: [u32; 8],
+    pub pre_state_root: Root,
+    pub post_state_root: Root,
+    /// Slot of the last block
+    pub slot: u64,
+    pub blocks: u32,
+    pub receipts: u32,
+}
+
+/// Decode the Lean guest's output into the journal and the (possibly empty) post-state.
+///
+/// Layout: stage u8 (0 = ok), operation kind u8, operation index u32 LE, reason,
+/// slot u64 LE, blocks applied u32 LE, then pre-state root, block root, post-state root
+/// and post-state.
+/// Reason, roots and post-state are u32 LE length + bytes.
+pub fn decode_lean_output(output: &[u8]) -> Option<(Eth2Journal, &[u8])> {
+    let mut pos = 0;
+    let stage = take(output, &mut pos, 1)?[0];
+    let kind = take(output, &mut pos, 1)?[0];
+    let index = u32::from_le_bytes(take(output, &mut pos, 4)?.try_into().ok()?);
+    let reason = core::str::from_utf8(take_bytes(output, &mut pos)?).ok()?;
+    let status = match stage {
+        0 => Eth2Status::Ok,
+        _ => Eth2Status::Error(Eth2Error::new(Stage::from_codes(stage, kind, index)?, reason)),
+    };
+    let slot = u64::from_le_bytes(take(output, &mut pos, 8)?.try_into().ok()?);
+    let blocks_applied = u32::from_le_bytes(take(output, &mut pos, 4)?.try_into().ok()?);
+    let pre_state_root = take_bytes(output, &mut pos)?.try_into().ok()?;
+    let block_root = take_bytes(output, &mut pos)?.try_into().ok()?;
+    let post_state_root = take_bytes(output, &mut pos)?.try_into().ok()?;
+    let post_state = take_bytes(output, &mut pos)?;
+    let journal = Eth2Journal { pre_state_root, block_root, post_state_root, slot, blocks_applied, status };
+    Some((journal, post_state))
+}
+
+fn take<'a>(data: &'a [u8], pos: &mut usize, n: usize) -> Option<&'a [u8]> {
+    let bytes = data.get(*pos..pos.checked_add(n)?)?;
+    *pos += n;
+    Some(bytes)
+}
+
+fn take_bytes<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    let len = u32::from_le_bytes(take(data, pos, 4)?.try_into().ok()?) as usize;
+    take(data, pos, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    // Lean output in the documented layout
+    fn lean_output(stage: u8, kind: u8, index: u32, reason: &str, roots: [&[u8]; 3], post_state: &[u8]) -> Vec<u8> {
+        fn bytes(out: &mut Vec<u8>, v: &[u8]) {
+            out.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            out.extend_from_slice(v);
+        }
+        let mut out = vec![stage, kind];
+        out.extend_from_slice(&index.to_le_bytes());
+        bytes(&mut out, reason.as_bytes());
+        out.extend_from_slice(&101u64.to_le_bytes());
+        out.extend_from_slice(&3u32.to_le_bytes());
+        for root in roots {
+            bytes(&mut out, root);
+        }
+        bytes(&mut out, post_state);
+        out
+    }
+
+    #[test]
+    fn decodes_ok_output() {
+        let output = lean_output(0, 0, 0, "", [&[1; 32], &[2; 32], &[3; 32]], b"state");
+        let (journal, post_state) = decode_lean_output(&output).unwrap();
+        assert_eq!(
+            journal,
+            Eth2Journal {
+                pre_state_root: [1; 32],
+                block_root: [2; 32],
+                post_state_root: [3; 32],
+                slot: 101,
+                blocks_applied: 3,
+                status: Eth2Status::Ok,
+            }
+        );
+        assert_eq!(post_state, b"state");
+    }
+
+    #[test]
+    fn decodes_operation_error() {
+        let output = lean_output(10, 2, 7, "attestation: bad source", [&[0; 32]; 3], b"");
+        let (journal, post_state) = decode_lean_output(&output).unwrap();
+        let stage = Stage::Operation { kind: OperationKind::Attestation, index: 7 };
+        assert_eq!(journal.status, Eth2Status::Error(Eth2Error::new(stage, "attestation: bad source")));
+        assert!(post_state.is_empty());
+        for code in 1..=13 {
+            assert!(Stage::from_codes(code, 0, 0).is_some(), "stage {}", code);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_output() {
+        let output = lean_output(0, 0, 0, "", [&[1; 32], &[2; 32], &[3; 32]], b"state");
+        for len in 0..output.len() {
+            assert!(decode_lean_output(&output[..len]).is_none(), "truncated to {}", len);
+        }
+        let unknown_stage = lean_output(14, 0, 0, "", [&[0; 32]; 3], b"");
+        assert!(decode_lean_output(&unknown_stage).is_none());
+        let unknown_kind = lean_output(10, 6, 0, "", [&[0; 32]; 3], b"");
+        assert!(decode_lean_output(&unknown_kind).is_none());
+        let short_root = lean_output(0, 0, 0, "", [&[0; 32], &[0; 31], &[0; 32]], b"");
+        assert!(decode_lean_output(&short_root).is_none());
+        let long_root = lean_output(0, 0, 0, "", [&[0; 32], &[0; 32], &[0; 33]], b"");
+        assert!(decode_lean_output(&long_root).is_none());
+    }
+}
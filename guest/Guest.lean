@@ -5,66 +5,91 @@ import Guest.Eth2
 def risc0_main (input : UInt32) : UInt32 :=
   sum input
 
+/-- Operation kind code (matches `eth2_journal::OperationKind::from_code`) -/
+private def operationKindCode : Eth2.OperationKind → UInt8
+  | .proposerSlashing => 0
+  | .attesterSlashing => 1
+  | .attestation => 2
+  | .deposit => 3
+  | .voluntaryExit => 4
+  | .blsToExecutionChange => 5
+
+/-- Stage code, operation kind code and operation index (matches `eth2_journal::Stage::from_codes`).
+    Codes 1 and 2 are the decode stages, emitted directly by `risc0_main_eth2`. -/
+private def stageCodes : Eth2.Stage → UInt8 × UInt8 × UInt32
+  | .slots => (3, 0, 0)
+  | .blockSignature => (4, 0, 0)
+  | .header => (5, 0, 0)
+  | .withdrawals => (6, 0, 0)
+  | .executionPayload => (7, 0, 0)
+  | .randao => (8, 0, 0)
+  | .eth1Data => (9, 0, 0)
+  | .operation kind index => (10, operationKindCode kind, index.toUInt32)
+  | .syncAggregate => (11, 0, 0)
+  | .stateRoot => (12, 0, 0)
+  | .unsupported => (13, 0, 0)
+
+/--
+  Journal fields plus the optional post-state, in the layout decoded by
+  `eth2_journal::decode_lean_output` in the guest-eth2-* wrappers:
+  stage u8 (0 = ok) ++ op kind u8 ++ op index u32 ++ reason ++ slot u64 ++ blocks applied u32
+  ++ pre_state_root ++ block_root ++ post_state_root ++ post_state
+  (reason, roots and post_state length-prefixed; post_state is empty unless requested).
+-/
+private def encodeJournal (codes : UInt8 × UInt8 × UInt32) (reason : String) (slot : UInt64)
+    (applied : Nat) (root : Eth2.Root) (postState : ByteArray) : ByteArray :=
+  let (stage, kind, index) := codes
+  let buf := Eth2.Encode.uint8 ByteArray.empty stage
+  let buf := Eth2.Encode.uint8 buf kind
+  let buf := Eth2.Encode.uint32 buf index
+  let buf := Eth2.Encode.bytes buf reason.toUTF8
+  let buf := Eth2.Encode.uint64 buf slot
+  let buf := Eth2.Encode.uint32 buf applied.toUInt32
+  -- hash_tree_root is stubbed, so all three roots share the placeholder
+  let buf := Eth2.Encode.bytes buf root
+  let buf := Eth2.Encode.bytes buf root
+  let buf := Eth2.Encode.bytes buf root
+  Eth2.Encode.bytes buf postState
+
 /--
   ETH2 state transition entry point for zkVM.
-  Input: serialized (BeaconState ++ SignedBeaconBlock)
-  Output: serialized post-state BeaconState (or error marker)
+  Input: serialized (BeaconState ++ Array SignedBeaconBlock ++ optional options:
+         validate_result bool ++ signature-check mask u32 ++ emit_post_state bool)
+  Blocks are applied in order; slot and block root refer to the last block, or to
+  the failing one on error.
+  Output: journal fields (typed error on failure) and, when emit_post_state is set,
+          the serialized final post-state. A non-zero signature-check mask or
+          validate_result fails with the unsupported stage, as both need real crypto.
 -/
 @[export risc0_main_eth2]
 def risc0_main_eth2 (input : @& ByteArray) : ByteArray :=
-  -- Decode: first BeaconState, then SignedBeaconBlock
+  let root := Eth2.hashTreeRoot (ByteArray.mk #[])
+  -- Decode: first BeaconState, then the non-empty block chain
   match Eth2.Decode.beaconState input 0 with
-  | none => ByteArray.mk #[0xFF]  -- decode error marker
+  | none => encodeJournal (1, 0, 0) "" 0 0 root ByteArray.empty
   | some (preState, off) =>
-    -- Decode signed block (simplified: just the block without signature)
-    match decodeSignedBlock input off with
-    | none => ByteArray.mk #[0xFE]  -- block decode error marker
-    | some (signedBlock, _) =>
-      match Eth2.stateTransition preState signedBlock with
-      | .ok postState => Eth2.serializeBeaconState postState
-      | .error errMsg =>
-        -- Encode error: 0xFD prefix + UTF-8 error message
-        let errBytes := errMsg.toUTF8
-        let result := ByteArray.emptyWithCapacity (1 + errBytes.size)
-        let result := result.push 0xFD
-        errBytes.foldl (init := result) fun acc b => acc.push b
-where
-  decodeSignedBlock (data : ByteArray) (off : Nat) : Option (Eth2.SignedBeaconBlock × Nat) := do
-    -- Decode BeaconBlock fields
-    let (slot, off) ← Eth2.Decode.uint64 data off
-    let (proposerIndex, off) ← Eth2.Decode.uint64 data off
-    let (parentRoot, off) ← Eth2.Decode.bytes data off
-    let (stateRoot, off) ← Eth2.Decode.bytes data off
-    -- Decode BeaconBlockBody
-    let (randaoReveal, off) ← Eth2.Decode.bytes data off
-    let (eth1Data, off) ← Eth2.Decode.eth1Data data off
-    let (graffiti, off) ← Eth2.Decode.bytes data off
-    -- Operations (arrays)
-    let (_proposerSlashingCount, off) ← Eth2.Decode.uint32 data off
-    -- Simplified: skip detailed operation decoding for now, use empty arrays
-    let body : Eth2.BeaconBlockBody := {
-      randaoReveal := randaoReveal
-      eth1Data := eth1Data
-      graffiti := graffiti
-      proposerSlashings := #[]
-      attesterSlashings := #[]
-      attestations := #[]
-      deposits := #[]
-      voluntaryExits := #[]
-      syncAggregate := default
-      executionPayload := default
-      blsToExecutionChanges := #[]
-    }
-    let block : Eth2.BeaconBlock := {
-      slot := slot
-      proposerIndex := proposerIndex
-      parentRoot := parentRoot
-      stateRoot := stateRoot
-      body := body
-    }
-    let (signature, off) ← Eth2.Decode.bytes data off
-    let signed : Eth2.SignedBeaconBlock := {
-      message := block
-      signature := signature
-    }
-    some (signed, off)
+    match Eth2.Decode.arrayOf Eth2.Decode.signedBeaconBlock input off with
+    | none => encodeJournal (2, 0, 0) "" 0 0 root ByteArray.empty
+    | some (blocks, off) =>
+      if blocks.isEmpty then encodeJournal (2, 0, 0) "" 0 0 root ByteArray.empty else
+      -- Trailing options. Missing options skip the state root check, stub every signature
+      -- and keep the post-state private.
+      let (validateResult, signatureChecks, emitPostState) := match Eth2.Decode.bool input off with
+        | none => (false, (0 : UInt32), false)
+        | some (v, off) => match Eth2.Decode.uint32 input off with
+          | none => (v, 0, false)
+          | some (mask, off) => match Eth2.Decode.bool input off with
+            | some (e, _) => (v, mask, e)
+            | none => (v, mask, false)
+      let slotOf (i : Nat) := blocks[min i (blocks.size - 1)]!.message.slot
+      -- BLS is stubbed: refuse real signature checks rather than attest to unchecked signatures
+      if signatureChecks != 0 then
+        encodeJournal (stageCodes .unsupported) "signature verification needs BLS, which this guest stubs"
+          (slotOf 0) 0 root ByteArray.empty
+      else
+      match Eth2.stateTransitionChain preState blocks validateResult with
+      | .ok postState =>
+        let postStateBytes := if emitPostState then Eth2.serializeBeaconState postState else ByteArray.empty
+        encodeJournal (0, 0, 0) "" (slotOf blocks.size) blocks.size root postStateBytes
+      | .error (applied, err) =>
+        encodeJournal (stageCodes err.stage) err.reason (slotOf applied) applied root ByteArray.empty
@@ -112,6 +112,12 @@ def MIN_GENESIS_ACTIVE_VALIDATOR_COUNT : Nat := 16384
 def MIN_GENESIS_TIME : UInt64 := 1606824000
 def GENESIS_DELAY : UInt64 := 604800
 
+-- ═══════════════════════════════════════════════
+-- Signature domain types
+-- ═══════════════════════════════════════════════
+def DOMAIN_BEACON_PROPOSER : DomainType := ByteArray.mk #[0x00, 0x00, 0x00, 0x00]
+def DOMAIN_SYNC_COMMITTEE : DomainType := ByteArray.mk #[0x07, 0x00, 0x00, 0x00]
+
 -- Inactivity scoring (Altair)
 def INACTIVITY_SCORE_BIAS : UInt64 := 4
 def INACTIVITY_SCORE_RECOVERY_RATE : UInt64 := 16
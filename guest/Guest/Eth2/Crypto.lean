@@ -1,14 +1,110 @@
 /-
-  Ethereum Consensus Layer — Cryptographic Primitives (Stubs)
+  Ethereum Consensus Layer — Cryptographic Primitives
 
-  All crypto operations are stubbed for zkVM verification.
-  hash_tree_root returns a deterministic placeholder.
+  SHA-256 is bound to the zkVM SHA-256 accelerator, with a software
+  implementation as its reference (needed for seeds and shuffling).
+  The remaining operations are stubbed for zkVM verification:
+  hash_tree_root returns a deterministic placeholder and
   BLS signature verification always returns true.
 -/
 import Guest.Eth2.Types
 
 namespace Eth2
 
+-- ═══════════════════════════════════════════════
+-- SHA-256 (FIPS 180-4)
+-- ═══════════════════════════════════════════════
+
+private def sha256K : Array UInt32 := #[
+  0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+  0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+  0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+  0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+  0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+  0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+  0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+  0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2]
+
+private def sha256Init : Array UInt32 :=
+  #[0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19]
+
+@[inline] private def rotr (x : UInt32) (n : UInt32) : UInt32 :=
+  (x >>> n) ||| (x <<< (32 - n))
+
+-- Compress one 64-byte block starting at `off` into the running state
+private def sha256Compress (state : Array UInt32) (msg : ByteArray) (off : Nat) : Array UInt32 := Id.run do
+  let mut w : Array UInt32 := Array.emptyWithCapacity 64
+  for i in [:16] do
+    let byteAt := fun j => (msg.get! (off + 4 * i + j)).toUInt32
+    w := w.push ((byteAt 0 <<< 24) ||| (byteAt 1 <<< 16) ||| (byteAt 2 <<< 8) ||| byteAt 3)
+  for i in [16:64] do
+    let x := w[i - 15]!
+    let y := w[i - 2]!
+    let s0 := rotr x 7 ^^^ rotr x 18 ^^^ (x >>> 3)
+    let s1 := rotr y 17 ^^^ rotr y 19 ^^^ (y >>> 10)
+    w := w.push (w[i - 16]! + s0 + w[i - 7]! + s1)
+  let mut a := state[0]!
+  let mut b := state[1]!
+  let mut c := state[2]!
+  let mut d := state[3]!
+  let mut e := state[4]!
+  let mut f := state[5]!
+  let mut g := state[6]!
+  let mut h := state[7]!
+  for i in [:64] do
+    let bigS1 := rotr e 6 ^^^ rotr e 11 ^^^ rotr e 25
+    let ch := (e &&& f) ^^^ (~~~e &&& g)
+    let t1 := h + bigS1 + ch + sha256K[i]! + w[i]!
+    let bigS0 := rotr a 2 ^^^ rotr a 13 ^^^ rotr a 22
+    let maj := (a &&& b) ^^^ (a &&& c) ^^^ (b &&& c)
+    let t2 := bigS0 + maj
+    h := g
+    g := f
+    f := e
+    e := d + t1
+    d := c
+    c := b
+    b := a
+    a := t1 + t2
+  return #[state[0]! + a, state[1]! + b, state[2]! + c, state[3]! + d,
+           state[4]! + e, state[5]! + f, state[6]! + g, state[7]! + h]
+
+-- SHA-256 of arbitrary bytes in software; the reference for `sha256`
+def sha256Soft (data : ByteArray) : Bytes32 := Id.run do
+  let bitLen := (data.size * 8).toUInt64
+  let mut msg := data.push 0x80
+  -- Zero-pad to 56 mod 64, then append the big-endian bit length
+  for _ in [:(119 - data.size % 64) % 64] do
+    msg := msg.push 0
+  for i in [:8] do
+    msg := msg.push (bitLen >>> (8 * (7 - i)).toUInt64).toUInt8
+  let mut state := sha256Init
+  for blk in [:msg.size / 64] do
+    state := sha256Compress state msg (blk * 64)
+  let mut out := ByteArray.emptyWithCapacity 32
+  for word in state do
+    out := out.push (word >>> 24).toUInt8
+    out := out.push (word >>> 16).toUInt8
+    out := out.push (word >>> 8).toUInt8
+    out := out.push word.toUInt8
+  return out
+
+-- SHA-256 of arbitrary bytes (spec `hash`). The guests link `lean_risc0_sha256` from
+-- lean-risc0-build's shims, which hashes with the zkVM accelerator as the Rust guest does
+@[extern "lean_risc0_sha256"]
+def sha256 (data : @& ByteArray) : Bytes32 := sha256Soft data
+
+private def toHex (bytes : ByteArray) : String :=
+  String.join (bytes.toList.map fun b => String.mk ((Nat.toDigits 16 (b.toNat + 256)).drop 1))
+
+-- Known answers from FIPS 180-4: empty, one block, and a message whose padding spills into a second block
+#guard toHex (sha256Soft ByteArray.empty) ==
+  "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+#guard toHex (sha256Soft "abc".toUTF8) ==
+  "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+#guard toHex (sha256Soft "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".toUTF8) ==
+  "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+
 -- Stub: returns a 32-byte zero root.
 -- In production this would compute the SSZ Merkle root.
 def hashTreeRoot (_data : ByteArray) : Root :=
@@ -8,6 +8,7 @@ import Guest.Eth2.Types
 import Guest.Eth2.Constants
 import Guest.Eth2.Crypto
 import Guest.Eth2.Containers
+import Guest.Eth2.Serialize
 
 namespace Eth2
 
@@ -157,41 +158,6 @@ def initiateValidatorExit (state : BeaconState) (index : ValidatorIndex) : Beaco
   }
   return { state with validators := state.validators.set! i newValidator }
 
--- Slash a validator (mark as slashed, initiate exit, apply penalty)
-def slashValidator (state : BeaconState) (slashedIndex : ValidatorIndex)
-    (whistleblowerIndex : Option ValidatorIndex) : BeaconState :=
-  let i := slashedIndex.toNat
-  if i >= state.validators.size then state
-  else
-    let epoch := getCurrentEpoch state
-    -- Initiate exit first
-    let state := initiateValidatorExit state slashedIndex
-    -- Mark as slashed, set withdrawable epoch
-    let validator := state.validators[i]!
-    let newValidator := { validator with
-      slashed := true
-      withdrawableEpoch :=
-        let we := epoch + EPOCHS_PER_SLASHINGS_VECTOR
-        if we > validator.withdrawableEpoch then we else validator.withdrawableEpoch
-    }
-    let state := { state with validators := state.validators.set! i newValidator }
-    -- Record slashing in slashings vector
-    let slashingsIdx := (epoch % EPOCHS_PER_SLASHINGS_VECTOR).toNat
-    let state :=
-      if slashingsIdx < state.slashings.size then
-        let newSlashing := state.slashings[slashingsIdx]! + newValidator.effectiveBalance
-        { state with slashings := state.slashings.set! slashingsIdx newSlashing }
-      else state
-    -- Apply minimum penalty
-    let penalty := newValidator.effectiveBalance / MIN_SLASHING_PENALTY_QUOTIENT_BELLATRIX
-    let state := decreaseBalance state slashedIndex penalty
-    -- Proposer reward
-    let proposerIndex := whistleblowerIndex.getD 0  -- simplified: use provided or 0
-    let whistleblowerReward := newValidator.effectiveBalance / WHISTLEBLOWER_REWARD_QUOTIENT
-    let proposerReward := whistleblowerReward * PROPOSER_WEIGHT / WEIGHT_DENOMINATOR
-    let state := increaseBalance state proposerIndex proposerReward
-    state
-
 -- ═══════════════════════════════════════════════
 -- Participation flag helpers (Altair)
 -- ═══════════════════════════════════════════════
@@ -226,14 +192,113 @@ def getBlockRootAtSlot (state : BeaconState) (slot : Slot) : Root :=
 def getBlockRoot (state : BeaconState) (epoch : Epoch) : Root :=
   getBlockRootAtSlot state (computeStartSlotAtEpoch epoch)
 
--- Get the beacon proposer index (stub: returns slot % validator_count)
--- In production this would use RANDAO-based shuffling
+-- ═══════════════════════════════════════════════
+-- Shuffling / proposer selection
+-- ═══════════════════════════════════════════════
+
+-- Seed for `epoch`, drawn from the randao mix MIN_SEED_LOOKAHEAD + 1 epochs back
+def getSeed (state : BeaconState) (epoch : Epoch) (domainType : DomainType) : Bytes32 :=
+  let mix := getRandaoMix state (epoch + EPOCHS_PER_HISTORICAL_VECTOR - MIN_SEED_LOOKAHEAD - 1)
+  sha256 (Encode.uint64 domainType epoch ++ mix)
+
+-- Little-endian UInt64 from the first 8 bytes
+private def bytesToUInt64 (bytes : ByteArray) : UInt64 := Id.run do
+  let mut v : UInt64 := 0
+  for i in [:8] do
+    v := v ||| ((bytes.get! i).toUInt64 <<< (8 * i).toUInt64)
+  return v
+
+-- Swap-or-not shuffle: position of `index` in a permutation of 0..indexCount
+def computeShuffledIndex (index indexCount : UInt64) (seed : Bytes32) : UInt64 := Id.run do
+  if indexCount == 0 then return index
+  let mut index := index
+  for round in [:SHUFFLE_ROUND_COUNT] do
+    let roundSeed := seed.push round.toUInt8
+    let pivot := bytesToUInt64 (sha256 roundSeed) % indexCount
+    let flip := (pivot + indexCount - index) % indexCount
+    let position := max index flip
+    let source := sha256 (Encode.uint32 roundSeed (position / 256).toUInt32)
+    let byte := source.get! ((position % 256) / 8).toNat
+    if (byte >>> (position % 8).toUInt8) &&& 1 == 1 then
+      index := flip
+  return index
+
+-- Sample shuffled candidates, accepting each with probability
+-- effective_balance / MAX_EFFECTIVE_BALANCE
+partial def computeProposerIndex (state : BeaconState) (indices : Array ValidatorIndex)
+    (seed : Bytes32) (i : UInt64 := 0) : ValidatorIndex :=
+  if indices.size == 0 then 0
+  else
+    let total := indices.size.toUInt64
+    let candidate := indices[(computeShuffledIndex (i % total) total seed).toNat]!
+    let randomByte := (sha256 (Encode.uint64 seed (i / 32))).get! (i % 32).toNat
+    let effectiveBalance := state.validators[candidate.toNat]!.effectiveBalance
+    if effectiveBalance * 255 >= MAX_EFFECTIVE_BALANCE * randomByte.toUInt64 then candidate
+    else computeProposerIndex state indices seed (i + 1)
+
+-- Get the beacon proposer index for the current slot
 def getBeaconProposerIndex (state : BeaconState) : ValidatorIndex :=
-  let activeIndices := getActiveValidatorIndices state (getCurrentEpoch state)
-  if activeIndices.size == 0 then 0
+  let epoch := getCurrentEpoch state
+  let seed := sha256 (Encode.uint64 (getSeed state epoch DOMAIN_BEACON_PROPOSER) state.slot)
+  computeProposerIndex state (getActiveValidatorIndices state epoch) seed
+
+-- Sample SYNC_COMMITTEE_SIZE members (with repeats) from the validators active next epoch,
+-- weighted by effective balance like computeProposerIndex; empty if none are active
+def getNextSyncCommitteeIndices (state : BeaconState) : Array ValidatorIndex := Id.run do
+  let epoch := getCurrentEpoch state + 1
+  let active := getActiveValidatorIndices state epoch
+  if active.size == 0 then return #[]
+  let total := active.size.toUInt64
+  let seed := getSeed state epoch DOMAIN_SYNC_COMMITTEE
+  let mut indices : Array ValidatorIndex := #[]
+  let mut i : UInt64 := 0
+  while indices.size < SYNC_COMMITTEE_SIZE do
+    let candidate := active[(computeShuffledIndex (i % total) total seed).toNat]!
+    let randomByte := (sha256 (Encode.uint64 seed (i / 32))).get! (i % 32).toNat
+    let effectiveBalance := state.validators[candidate.toNat]!.effectiveBalance
+    if effectiveBalance * 255 >= MAX_EFFECTIVE_BALANCE * randomByte.toUInt64 then
+      indices := indices.push candidate
+    i := i + 1
+  return indices
+
+-- ═══════════════════════════════════════════════
+-- Slashing
+-- ═══════════════════════════════════════════════
+
+-- Slash a validator (mark as slashed, initiate exit, apply penalty)
+def slashValidator (state : BeaconState) (slashedIndex : ValidatorIndex)
+    (whistleblowerIndex : Option ValidatorIndex) : BeaconState :=
+  let i := slashedIndex.toNat
+  if i >= state.validators.size then state
   else
-    let idx := (state.slot % activeIndices.size.toUInt64).toNat
-    if idx < activeIndices.size then activeIndices[idx]!
-    else 0
+    let epoch := getCurrentEpoch state
+    -- Initiate exit first
+    let state := initiateValidatorExit state slashedIndex
+    -- Mark as slashed, set withdrawable epoch
+    let validator := state.validators[i]!
+    let newValidator := { validator with
+      slashed := true
+      withdrawableEpoch :=
+        let we := epoch + EPOCHS_PER_SLASHINGS_VECTOR
+        if we > validator.withdrawableEpoch then we else validator.withdrawableEpoch
+    }
+    let state := { state with validators := state.validators.set! i newValidator }
+    -- Record slashing in slashings vector
+    let slashingsIdx := (epoch % EPOCHS_PER_SLASHINGS_VECTOR).toNat
+    let state :=
+      if slashingsIdx < state.slashings.size then
+        let newSlashing := state.slashings[slashingsIdx]! + newValidator.effectiveBalance
+        { state with slashings := state.slashings.set! slashingsIdx newSlashing }
+      else state
+    -- Apply minimum penalty
+    let penalty := newValidator.effectiveBalance / MIN_SLASHING_PENALTY_QUOTIENT_BELLATRIX
+    let state := decreaseBalance state slashedIndex penalty
+    -- The proposer takes its share of the reward; the whistleblower (the proposer by default) the rest
+    let proposerIndex := getBeaconProposerIndex state
+    let whistleblowerIndex := whistleblowerIndex.getD proposerIndex
+    let whistleblowerReward := newValidator.effectiveBalance / WHISTLEBLOWER_REWARD_QUOTIENT
+    let proposerReward := whistleblowerReward * PROPOSER_WEIGHT / WEIGHT_DENOMINATOR
+    let state := increaseBalance state proposerIndex proposerReward
+    increaseBalance state whistleblowerIndex (whistleblowerReward - proposerReward)
 
 end Eth2
@@ -279,6 +279,142 @@ def historicalSummary : DecodeM HistoricalSummary := fun data off => do
   let (ssr, off) ← bytes data off
   some ({ blockSummaryRoot := bsr, stateSummaryRoot := ssr }, off)
 
+-- Block operations
+
+def signedBeaconBlockHeader : DecodeM SignedBeaconBlockHeader := fun data off => do
+  let (msg, off) ← beaconBlockHeader data off
+  let (sig, off) ← bytes data off
+  some ({ message := msg, signature := sig }, off)
+
+def proposerSlashing : DecodeM ProposerSlashing := fun data off => do
+  let (h1, off) ← signedBeaconBlockHeader data off
+  let (h2, off) ← signedBeaconBlockHeader data off
+  some ({ signedHeader1 := h1, signedHeader2 := h2 }, off)
+
+def attestationData : DecodeM AttestationData := fun data off => do
+  let (sl, off) ← uint64 data off
+  let (ix, off) ← uint64 data off
+  let (bbr, off) ← bytes data off
+  let (src, off) ← checkpoint data off
+  let (tgt, off) ← checkpoint data off
+  some ({ slot := sl, index := ix, beaconBlockRoot := bbr, source := src, target := tgt }, off)
+
+def indexedAttestation : DecodeM IndexedAttestation := fun data off => do
+  let (ai, off) ← arrayOf uint64 data off
+  let (ad, off) ← attestationData data off
+  let (sig, off) ← bytes data off
+  some ({ attestingIndices := ai, data := ad, signature := sig }, off)
+
+def attesterSlashing : DecodeM AttesterSlashing := fun data off => do
+  let (a1, off) ← indexedAttestation data off
+  let (a2, off) ← indexedAttestation data off
+  some ({ attestation1 := a1, attestation2 := a2 }, off)
+
+def attestation : DecodeM Attestation := fun data off => do
+  let (ab, off) ← bytes data off
+  let (ad, off) ← attestationData data off
+  let (sig, off) ← bytes data off
+  some ({ aggregationBits := ab, data := ad, signature := sig }, off)
+
+def depositData : DecodeM DepositData := fun data off => do
+  let (pk, off) ← bytes data off
+  let (wc, off) ← bytes data off
+  let (amt, off) ← uint64 data off
+  let (sig, off) ← bytes data off
+  some ({ pubkey := pk, withdrawalCredentials := wc, amount := amt, signature := sig }, off)
+
+def deposit : DecodeM Deposit := fun data off => do
+  let (pf, off) ← arrayOf bytes data off
+  let (dd, off) ← depositData data off
+  some ({ proof := pf, data := dd }, off)
+
+def signedVoluntaryExit : DecodeM SignedVoluntaryExit := fun data off => do
+  let (ep, off) ← uint64 data off
+  let (vi, off) ← uint64 data off
+  let (sig, off) ← bytes data off
+  some ({ message := { epoch := ep, validatorIndex := vi }, signature := sig }, off)
+
+def signedBlsToExecutionChange : DecodeM SignedBLSToExecutionChange := fun data off => do
+  let (vi, off) ← uint64 data off
+  let (pk, off) ← bytes data off
+  let (addr, off) ← bytes data off
+  let (sig, off) ← bytes data off
+  some ({
+    message := { validatorIndex := vi, fromBlsPubkey := pk, toExecutionAddress := addr },
+    signature := sig
+  }, off)
+
+def syncAggregate : DecodeM SyncAggregate := fun data off => do
+  let (bits, off) ← bytes data off
+  let (sig, off) ← bytes data off
+  some ({ syncCommitteeBits := bits, syncCommitteeSignature := sig }, off)
+
+def withdrawal : DecodeM Withdrawal := fun data off => do
+  let (ix, off) ← uint64 data off
+  let (vi, off) ← uint64 data off
+  let (addr, off) ← bytes data off
+  let (amt, off) ← uint64 data off
+  some ({ index := ix, validatorIndex := vi, address := addr, amount := amt }, off)
+
+def executionPayload : DecodeM ExecutionPayload := fun data off => do
+  let (ph, off) ← bytes data off
+  let (fr, off) ← bytes data off
+  let (sr, off) ← bytes data off
+  let (rr, off) ← bytes data off
+  let (lb, off) ← bytes data off
+  let (pr, off) ← bytes data off
+  let (bn, off) ← uint64 data off
+  let (gl, off) ← uint64 data off
+  let (gu, off) ← uint64 data off
+  let (ts, off) ← uint64 data off
+  let (ed, off) ← bytes data off
+  let (bf, off) ← uint64 data off
+  let (bh, off) ← bytes data off
+  let (txs, off) ← arrayOf bytes data off
+  let (ws, off) ← arrayOf withdrawal data off
+  some ({
+    parentHash := ph, feeRecipient := fr, stateRoot := sr, receiptsRoot := rr,
+    logsBloom := lb, prevRandao := pr, blockNumber := bn, gasLimit := gl,
+    gasUsed := gu, timestamp := ts, extraData := ed, baseFeePerGas := bf,
+    blockHash := bh, transactions := txs, withdrawals := ws
+  }, off)
+
+-- Block
+
+def beaconBlockBody : DecodeM BeaconBlockBody := fun data off => do
+  let (rr, off) ← bytes data off
+  let (e1d, off) ← eth1Data data off
+  let (gr, off) ← bytes data off
+  -- Operations
+  let (ps, off) ← arrayOf proposerSlashing data off
+  let (asl, off) ← arrayOf attesterSlashing data off
+  let (atts, off) ← arrayOf attestation data off
+  let (deps, off) ← arrayOf deposit data off
+  let (exits, off) ← arrayOf signedVoluntaryExit data off
+  -- Altair / Bellatrix / Capella
+  let (sa, off) ← syncAggregate data off
+  let (ep, off) ← executionPayload data off
+  let (btec, off) ← arrayOf signedBlsToExecutionChange data off
+  some ({
+    randaoReveal := rr, eth1Data := e1d, graffiti := gr,
+    proposerSlashings := ps, attesterSlashings := asl, attestations := atts,
+    deposits := deps, voluntaryExits := exits,
+    syncAggregate := sa, executionPayload := ep, blsToExecutionChanges := btec
+  }, off)
+
+def beaconBlock : DecodeM BeaconBlock := fun data off => do
+  let (sl, off) ← uint64 data off
+  let (pi, off) ← uint64 data off
+  let (pr, off) ← bytes data off
+  let (sr, off) ← bytes data off
+  let (body, off) ← beaconBlockBody data off
+  some ({ slot := sl, proposerIndex := pi, parentRoot := pr, stateRoot := sr, body := body }, off)
+
+def signedBeaconBlock : DecodeM SignedBeaconBlock := fun data off => do
+  let (msg, off) ← beaconBlock data off
+  let (sig, off) ← bytes data off
+  some ({ message := msg, signature := sig }, off)
+
 def beaconState : DecodeM BeaconState := fun data off => do
   -- Versioning
   let (gt, off) ← uint64 data off
@@ -14,14 +14,14 @@ import Guest.Eth2.Transition.Block.ExecutionPayload
 
 namespace Eth2
 
-def processBlock (state : BeaconState) (block : BeaconBlock) : STFResult BeaconState := do
-  let state ← processBlockHeader state block
-  let state ← processWithdrawals state block.body.executionPayload
-  let state ← processExecutionPayload state block.body.executionPayload
-  let state ← processRandao state block.body
-  let state ← processEth1Data state block.body
+def processBlock (state : BeaconState) (block : BeaconBlock) : Except STFError BeaconState := do
+  let state ← (processBlockHeader state block).atStage .header
+  let state ← (processWithdrawals state block.body.executionPayload).atStage .withdrawals
+  let state ← (processExecutionPayload state block.body.executionPayload).atStage .executionPayload
+  let state ← (processRandao state block.body).atStage .randao
+  let state ← (processEth1Data state block.body).atStage .eth1Data
   let state ← processOperations state block.body
-  let state ← processSyncAggregate state block.body.syncAggregate
+  let state ← (processSyncAggregate state block.body.syncAggregate).atStage .syncAggregate
   .ok state
 
 end Eth2
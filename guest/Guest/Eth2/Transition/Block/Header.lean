@@ -33,6 +33,43 @@ instance : Monad STFResult where
 
 end STFResult
 
+-- Operation lists in block body order
+inductive OperationKind
+  | proposerSlashing
+  | attesterSlashing
+  | attestation
+  | deposit
+  | voluntaryExit
+  | blsToExecutionChange
+  deriving Repr, Inhabited
+
+-- Transition stage that failed (mirrors eth2_journal::Stage)
+inductive Stage
+  | slots
+  | blockSignature
+  | header
+  | withdrawals
+  | executionPayload
+  | randao
+  | eth1Data
+  | operation (kind : OperationKind) (index : Nat)  -- index within that operation list
+  | syncAggregate
+  | stateRoot
+  | unsupported  -- the input asked for a check this guest cannot perform
+  deriving Repr, Inhabited
+
+-- Typed transition error: failing stage plus the step's error message
+structure STFError where
+  stage  : Stage
+  reason : String
+  deriving Repr, Inhabited
+
+-- Tag a processing step's error message with the stage it came from
+def STFResult.atStage (r : STFResult α) (stage : Stage) : Except STFError α :=
+  match r with
+  | .ok v => .ok v
+  | .error msg => .error { stage := stage, reason := msg }
+
 def processBlockHeader (state : BeaconState) (block : BeaconBlock) : STFResult BeaconState :=
   -- Verify slot matches
   if block.slot != state.slot then
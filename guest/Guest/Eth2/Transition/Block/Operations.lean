@@ -13,20 +13,25 @@ import Guest.Eth2.Transition.Block.Operations.BlsToExecution
 
 namespace Eth2
 
--- Helper to fold over an array with a fallible operation
-private def foldSTF (state : BeaconState) (items : Array α)
-    (f : BeaconState → α → STFResult BeaconState) : STFResult BeaconState :=
-  items.foldlM (init := state) fun s item => f s item
+-- Helper to fold over an array with a fallible operation;
+-- errors carry the operation kind and the item's index in the list
+private def foldSTF (state : BeaconState) (items : Array α) (kind : OperationKind)
+    (f : BeaconState → α → STFResult BeaconState) : Except STFError BeaconState := do
+  let init : BeaconState × Nat := (state, 0)
+  let (state, _) ← items.foldlM (init := init) fun (s, i) item => do
+    let s ← (f s item).atStage (.operation kind i)
+    pure (s, i + 1)
+  pure state
 
-def processOperations (state : BeaconState) (body : BeaconBlockBody) : STFResult BeaconState := do
+def processOperations (state : BeaconState) (body : BeaconBlockBody) : Except STFError BeaconState := do
   -- Verify deposit count (stub: skip since we don't track eth1 deposit tree)
   -- Process all operations in spec order
-  let state ← foldSTF state body.proposerSlashings processProposerSlashing
-  let state ← foldSTF state body.attesterSlashings processAttesterSlashing
-  let state ← foldSTF state body.attestations processAttestation
-  let state ← foldSTF state body.deposits processDeposit
-  let state ← foldSTF state body.voluntaryExits processVoluntaryExit
-  let state ← foldSTF state body.blsToExecutionChanges processBlsToExecutionChange
+  let state ← foldSTF state body.proposerSlashings .proposerSlashing processProposerSlashing
+  let state ← foldSTF state body.attesterSlashings .attesterSlashing processAttesterSlashing
+  let state ← foldSTF state body.attestations .attestation processAttestation
+  let state ← foldSTF state body.deposits .deposit processDeposit
+  let state ← foldSTF state body.voluntaryExits .voluntaryExit processVoluntaryExit
+  let state ← foldSTF state body.blsToExecutionChanges .blsToExecutionChange processBlsToExecutionChange
   .ok state
 
 end Eth2
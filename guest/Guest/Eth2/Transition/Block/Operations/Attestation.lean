@@ -18,22 +18,29 @@ def processAttestation (state : BeaconState) (attestation : Attestation) : STFRe
   if data.target.epoch != previousEpoch && data.target.epoch != currentEpoch then
     .error "attestation: invalid target epoch"
   else
+    -- Determine which participation array to update
+    let isPreviousEpoch := data.target.epoch == previousEpoch
+    let justifiedCheckpoint := if isPreviousEpoch
+      then state.previousJustifiedCheckpoint
+      else state.currentJustifiedCheckpoint
     -- Verify slot is within bounds
     let attestationEpoch := computeEpochAtSlot data.slot
     if attestationEpoch != data.target.epoch then
       .error "attestation: slot/epoch mismatch"
+    -- Included at least MIN_ATTESTATION_INCLUSION_DELAY and at most an epoch after its slot
+    -- (in Nat, so neither bound wraps)
+    else if data.slot.toNat + MIN_ATTESTATION_INCLUSION_DELAY.toNat > state.slot.toNat ||
+        state.slot.toNat > data.slot.toNat + SLOTS_PER_EPOCH.toNat then
+      .error "attestation: outside inclusion window"
+    -- The source must be the justified checkpoint, root included
+    else if data.source.epoch != justifiedCheckpoint.epoch ||
+        data.source.root != justifiedCheckpoint.root then
+      .error "attestation: source mismatch"
     else
       -- Stub: skip committee validation, signature verification
-      -- Determine which participation array to update
-      let isPreviousEpoch := data.target.epoch == previousEpoch
       let inclusionDelay := state.slot - data.slot
       -- Determine participation flags from inclusion delay and correctness
-      let justifiedCheckpoint := if isPreviousEpoch
-        then state.previousJustifiedCheckpoint
-        else state.currentJustifiedCheckpoint
-      let isMatchingSource := data.source.epoch == justifiedCheckpoint.epoch
-      let isMatchingTarget := isMatchingSource &&
-        data.target.root == getBlockRoot state data.target.epoch
+      let isMatchingTarget := data.target.root == getBlockRoot state data.target.epoch
       let isMatchingHead := isMatchingTarget &&
         data.beaconBlockRoot == getBlockRootAtSlot state data.slot
       -- Update participation flags for all attesting validators
@@ -62,7 +69,7 @@ def processAttestation (state : BeaconState) (attestation : Attestation) : STFRe
             if i < participation.size then
               let flags := participation[i]!
               let mut newFlags := flags
-              if isMatchingSource && inclusionDelay <= (integerSquareroot SLOTS_PER_EPOCH) then
+              if inclusionDelay <= (integerSquareroot SLOTS_PER_EPOCH) then
                 if !hasFlag flags TIMELY_SOURCE_FLAG_INDEX then
                   newFlags := addFlag newFlags TIMELY_SOURCE_FLAG_INDEX
                   proposerRewardNum := proposerRewardNum + getBaseReward state validatorIdx * TIMELY_SOURCE_WEIGHT
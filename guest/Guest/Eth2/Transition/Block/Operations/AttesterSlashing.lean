@@ -22,11 +22,18 @@ private def isSlashableAttestationData (data1 data2 : AttestationData) : Bool :=
   -- Surround vote: att1 surrounds att2
   (data1.source.epoch < data2.source.epoch && data2.target.epoch < data1.target.epoch)
 
+-- Structural part of is_valid_indexed_attestation: indices non-empty, sorted and unique
+private def hasValidAttestingIndices (attestation : IndexedAttestation) : Bool :=
+  let indices := attestation.attestingIndices
+  indices.size > 0 && (List.range (indices.size - 1)).all fun i => indices[i]! < indices[i + 1]!
+
 def processAttesterSlashing (state : BeaconState) (slashing : AttesterSlashing) : STFResult BeaconState :=
   let att1 := slashing.attestation1
   let att2 := slashing.attestation2
   if !isSlashableAttestationData att1.data att2.data then
     .error "attester slashing: not slashable"
+  else if !(hasValidAttestingIndices att1 && hasValidAttestingIndices att2) then
+    .error "attester slashing: invalid attesting indices"
   else
     -- Stub: skip signature verification on indexed attestations
     -- Find intersection of attesting indices
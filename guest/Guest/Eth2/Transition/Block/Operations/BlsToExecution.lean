@@ -4,6 +4,7 @@
   Reference: https://eth2book.info/latest/part3/transition/block/#bls-to-execution-changes
 -/
 import Guest.Eth2.Helpers
+import Guest.Eth2.Crypto
 import Guest.Eth2.Transition.Block.Header
 
 namespace Eth2
@@ -29,10 +30,14 @@ def processBlsToExecutionChange (state : BeaconState) (change : SignedBLSToExecu
   if validatorIdx < state.validators.size then
     let validator := state.validators[validatorIdx]!
     -- Verify current withdrawal credentials start with BLS_WITHDRAWAL_PREFIX (0x00)
-    if validator.withdrawalCredentials.size > 0 && validator.withdrawalCredentials.get! 0 != 0x00 then
+    let credentials := validator.withdrawalCredentials
+    if credentials.size == 0 || credentials.get! 0 != 0x00 then
       .error "bls_to_execution: wrong withdrawal prefix"
+    -- The credentials commit to the key the change is signed with: hash(pubkey)[1:]
+    else if credentials.extract 1 credentials.size != (sha256 msg.fromBlsPubkey).extract 1 32 then
+      .error "bls_to_execution: pubkey mismatch"
     else
-      -- Stub: skip BLS pubkey match and signature verification
+      -- Stub: skip signature verification
       let newCreds := buildEth1WithdrawalCredentials msg.toExecutionAddress
       let newValidator := { validator with withdrawalCredentials := newCreds }
       .ok { state with validators := state.validators.set! validatorIdx newValidator }
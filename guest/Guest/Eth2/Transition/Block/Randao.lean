@@ -23,8 +23,8 @@ def processRandao (state : BeaconState) (body : BeaconBlockBody) : STFResult Bea
   -- In production: verify BLS signature of epoch by proposer
   let currentEpoch := getCurrentEpoch state
   let mix := getRandaoMix state currentEpoch
-  -- Mix in the reveal (XOR with hash of reveal)
-  let revealHash := hashTreeRoot body.randaoReveal
+  -- Mix in the reveal: XOR with its hash
+  let revealHash := sha256 body.randaoReveal
   let newMix := xorBytes mix revealHash
   let idx := (currentEpoch % EPOCHS_PER_HISTORICAL_VECTOR).toNat
   if idx < state.randaoMixes.size then
@@ -72,6 +72,10 @@ def processWithdrawals (state : BeaconState) (payload : ExecutionPayload) : STFR
   -- Verify withdrawal count matches
   if payload.withdrawals.size != expectedWithdrawals.size then
     .error "withdrawals: count mismatch"
+  else if !(payload.withdrawals.zip expectedWithdrawals).all (fun (w, e) =>
+      w.index == e.index && w.validatorIndex == e.validatorIndex &&
+      w.address == e.address && w.amount == e.amount) then
+    .error "withdrawals: withdrawal does not match the expected one"
   else
     let state := Id.run do
       let mut state := state
@@ -84,13 +88,18 @@ def processWithdrawals (state : BeaconState) (payload : ExecutionPayload) : STFR
         | some w => w.index + 1
         | none => state.nextWithdrawalIndex
       else state.nextWithdrawalIndex
-    let numChecked := min state.validators.size MAX_VALIDATORS_PER_WITHDRAWALS_SWEEP.toNat
-    let nextValidatorIdx :=
-      if state.validators.size == 0 then 0
-      else (state.nextWithdrawalValidatorIndex.toNat + numChecked) % state.validators.size |>.toUInt64
+    -- A full payload resumes the sweep after its last withdrawal; otherwise it moves on a whole sweep
+    let n := state.validators.size
+    let nextValidatorIdx : Nat :=
+      if n == 0 then 0
+      else match expectedWithdrawals.back? with
+        | some w =>
+          if expectedWithdrawals.size == MAX_WITHDRAWALS_PER_PAYLOAD then (w.validatorIndex.toNat + 1) % n
+          else (state.nextWithdrawalValidatorIndex.toNat + MAX_VALIDATORS_PER_WITHDRAWALS_SWEEP.toNat) % n
+        | none => (state.nextWithdrawalValidatorIndex.toNat + MAX_VALIDATORS_PER_WITHDRAWALS_SWEEP.toNat) % n
     .ok { state with
       nextWithdrawalIndex := nextIndex
-      nextWithdrawalValidatorIndex := nextValidatorIdx
+      nextWithdrawalValidatorIndex := nextValidatorIdx.toUInt64
     }
 
 end Eth2
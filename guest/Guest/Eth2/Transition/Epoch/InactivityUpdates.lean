@@ -8,41 +8,48 @@ import Guest.Eth2.Helpers
 namespace Eth2
 
 -- Check if the chain is in an inactivity leak
--- (more than MIN_EPOCHS_TO_INACTIVITY_PENALTY epochs since finalization)
+-- (more than MIN_EPOCHS_TO_INACTIVITY_PENALTY epochs between the previous epoch and finalization)
 def isInInactivityLeak (state : BeaconState) : Bool :=
-  let currentEpoch := getCurrentEpoch state
+  let previousEpoch := getPreviousEpoch state
   let finalizedEpoch := state.finalizedCheckpoint.epoch
-  currentEpoch > finalizedEpoch + MIN_EPOCHS_TO_INACTIVITY_PENALTY
+  previousEpoch > finalizedEpoch && previousEpoch - finalizedEpoch > MIN_EPOCHS_TO_INACTIVITY_PENALTY
+
+-- Validators active in the previous epoch, or slashed and not yet withdrawable
+def getEligibleValidatorIndices (state : BeaconState) : Array ValidatorIndex := Id.run do
+  let previousEpoch := getPreviousEpoch state
+  let mut result : Array ValidatorIndex := #[]
+  for i in [:state.validators.size] do
+    let v := state.validators[i]!
+    if isActiveValidator v previousEpoch || (v.slashed && previousEpoch + 1 < v.withdrawableEpoch) then
+      result := result.push i.toUInt64
+  return result
+
+-- Membership mask of the unslashed validators active in the previous epoch with a flag set
+def getUnslashedParticipatingMask (state : BeaconState) (flagIndex : Nat) : Array Bool := Id.run do
+  let mut mask := Array.replicate state.validators.size false
+  for idx in getActiveValidatorIndices state (getPreviousEpoch state) do
+    let i := idx.toNat
+    if i < state.previousEpochParticipation.size then
+      if hasFlag state.previousEpochParticipation[i]! flagIndex && !state.validators[i]!.slashed then
+        mask := mask.set! i true
+  return mask
 
 def processInactivityUpdates (state : BeaconState) : BeaconState := Id.run do
   let currentEpoch := getCurrentEpoch state
   -- Skip genesis epoch
   if currentEpoch == 0 then return state
-  let _previousEpoch := getPreviousEpoch state
-  let activeIndices := getActiveValidatorIndices state currentEpoch
+  let matchingTarget := getUnslashedParticipatingMask state TIMELY_TARGET_FLAG_INDEX
   let inLeak := isInInactivityLeak state
   let mut scores := state.inactivityScores
-  for idx in activeIndices do
+  for idx in getEligibleValidatorIndices state do
     let i := idx.toNat
     if i < scores.size then
       let score := scores[i]!
-      -- Check if validator had timely target participation in previous epoch
-      let participated :=
-        if i < state.previousEpochParticipation.size then
-          hasFlag state.previousEpochParticipation[i]! TIMELY_TARGET_FLAG_INDEX
-        else false
-      let newScore :=
-        if participated then
-          -- Decrease score towards 0
-          if score >= INACTIVITY_SCORE_RECOVERY_RATE then
-            score - INACTIVITY_SCORE_RECOVERY_RATE
-          else 0
-        else
-          -- Increase score if in leak
-          if inLeak then score + INACTIVITY_SCORE_BIAS
-          else score
-      scores := scores.set! i newScore
-  -- Ignore previousEpoch usage warning - it's used conceptually
+      -- Timely target voters recover by 1, everyone else gains the bias
+      let score := if matchingTarget[i]! then score - min 1 score else score + INACTIVITY_SCORE_BIAS
+      -- Outside a leak every score also recovers
+      let score := if inLeak then score else score - min INACTIVITY_SCORE_RECOVERY_RATE score
+      scores := scores.set! i score
   return { state with inactivityScores := scores }
 
 end Eth2
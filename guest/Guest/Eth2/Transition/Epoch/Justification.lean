@@ -32,8 +32,8 @@ private def shiftJustificationBits (bits : ByteArray) : ByteArray :=
     bits.set! 0 newByte
   else bits
 
--- Compute total balance of validators with a specific participation flag set
-private def getParticipatingBalance (state : BeaconState) (epoch : Epoch)
+-- Compute total balance of unslashed validators with a specific participation flag set
+private def getUnslashedParticipatingBalance (state : BeaconState) (epoch : Epoch)
     (flagIndex : Nat) : Gwei := Id.run do
   let participation := if epoch == getCurrentEpoch state
     then state.currentEpochParticipation
@@ -45,7 +45,8 @@ private def getParticipatingBalance (state : BeaconState) (epoch : Epoch)
     if i < participation.size then
       if hasFlag participation[i]! flagIndex then
         if i < state.validators.size then
-          total := total + state.validators[i]!.effectiveBalance
+          if !state.validators[i]!.slashed then
+            total := total + state.validators[i]!.effectiveBalance
   if total < EFFECTIVE_BALANCE_INCREMENT then EFFECTIVE_BALANCE_INCREMENT else total
 
 def processJustificationAndFinalization (state : BeaconState) : BeaconState :=
@@ -55,18 +56,23 @@ def processJustificationAndFinalization (state : BeaconState) : BeaconState :=
   else
     let previousEpoch := getPreviousEpoch state
     let totalActiveBalance := getTotalActiveBalance state
-    -- Target balance = validators that attested to correct target
-    let previousTargetBalance := getParticipatingBalance state previousEpoch TIMELY_TARGET_FLAG_INDEX
-    let currentTargetBalance := getParticipatingBalance state currentEpoch TIMELY_TARGET_FLAG_INDEX
-    -- Shift justification bits
-    let bits := shiftJustificationBits state.justificationBits
-    let state := { state with justificationBits := bits }
+    -- Target balance = unslashed validators that attested to correct target
+    let previousTargetBalance := getUnslashedParticipatingBalance state previousEpoch TIMELY_TARGET_FLAG_INDEX
+    let currentTargetBalance := getUnslashedParticipatingBalance state currentEpoch TIMELY_TARGET_FLAG_INDEX
+    -- Finalization is decided against the checkpoints from before this epoch's justification
+    let oldPreviousJustified := state.previousJustifiedCheckpoint
+    let oldCurrentJustified := state.currentJustifiedCheckpoint
+    -- Rotate checkpoints and shift justification bits
+    let state := { state with
+      previousJustifiedCheckpoint := state.currentJustifiedCheckpoint
+      justificationBits := shiftJustificationBits state.justificationBits
+    }
     -- Justify previous epoch if 2/3 supermajority
     let state :=
       if previousTargetBalance * 3 >= totalActiveBalance * 2 then
         { state with
           justificationBits := setJustificationBit state.justificationBits 1
-          previousJustifiedCheckpoint := {
+          currentJustifiedCheckpoint := {
             epoch := previousEpoch
             root := getBlockRoot state previousEpoch
           }
@@ -85,32 +91,32 @@ def processJustificationAndFinalization (state : BeaconState) : BeaconState :=
       else state
     -- Finalization rules (check 2/3/4 epoch chains)
     let bits := state.justificationBits
-    -- Rule 1: epochs (n-3, n-2, n-1, n) all justified → finalize n-3
+    -- Rule 1: 2nd/3rd/4th most recent epochs justified, 2nd using 4th as source
     let state :=
       if getJustificationBit bits 1 && getJustificationBit bits 2 && getJustificationBit bits 3 then
-        if state.previousJustifiedCheckpoint.epoch + 3 == currentEpoch then
-          { state with finalizedCheckpoint := state.previousJustifiedCheckpoint }
+        if oldPreviousJustified.epoch + 3 == currentEpoch then
+          { state with finalizedCheckpoint := oldPreviousJustified }
         else state
       else state
-    -- Rule 2: epochs (n-2, n-1, n) justified → finalize n-2
+    -- Rule 2: 2nd/3rd most recent epochs justified, 2nd using 3rd as source
     let state :=
       if getJustificationBit bits 1 && getJustificationBit bits 2 then
-        if state.previousJustifiedCheckpoint.epoch + 2 == currentEpoch then
-          { state with finalizedCheckpoint := state.previousJustifiedCheckpoint }
+        if oldPreviousJustified.epoch + 2 == currentEpoch then
+          { state with finalizedCheckpoint := oldPreviousJustified }
         else state
       else state
-    -- Rule 3: epochs (n-2, n-1, n) with current justified → finalize n-2
+    -- Rule 3: 1st/2nd/3rd most recent epochs justified, 1st using 3rd as source
     let state :=
       if getJustificationBit bits 0 && getJustificationBit bits 1 && getJustificationBit bits 2 then
-        if state.currentJustifiedCheckpoint.epoch + 2 == currentEpoch then
-          { state with finalizedCheckpoint := state.currentJustifiedCheckpoint }
+        if oldCurrentJustified.epoch + 2 == currentEpoch then
+          { state with finalizedCheckpoint := oldCurrentJustified }
         else state
       else state
-    -- Rule 4: epochs (n-1, n) justified → finalize n-1
+    -- Rule 4: 1st/2nd most recent epochs justified, 1st using 2nd as source
     let state :=
       if getJustificationBit bits 0 && getJustificationBit bits 1 then
-        if state.currentJustifiedCheckpoint.epoch + 1 == currentEpoch then
-          { state with finalizedCheckpoint := state.currentJustifiedCheckpoint }
+        if oldCurrentJustified.epoch + 1 == currentEpoch then
+          { state with finalizedCheckpoint := oldCurrentJustified }
         else state
       else state
     state
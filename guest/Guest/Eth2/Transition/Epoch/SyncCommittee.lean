@@ -11,12 +11,19 @@ namespace Eth2
 def processSyncCommitteeUpdates (state : BeaconState) : BeaconState :=
   let nextEpoch := getCurrentEpoch state + 1
   if nextEpoch % EPOCHS_PER_SYNC_COMMITTEE_PERIOD == 0 then
-    -- In production: compute new committee from RANDAO. Here: rotate existing.
-    { state with
-      currentSyncCommittee := state.nextSyncCommittee
-      -- Stub: next committee stays the same (would be computed from randao in production)
-      nextSyncCommittee := state.nextSyncCommittee
-    }
+    let indices := getNextSyncCommitteeIndices state
+    -- With no validator active next epoch (the spec assumes one is), the next committee is kept
+    if indices.size == 0 then
+      { state with currentSyncCommittee := state.nextSyncCommittee }
+    else
+      { state with
+        currentSyncCommittee := state.nextSyncCommittee
+        nextSyncCommittee := {
+          pubkeys := indices.map fun i => state.validators[i.toNat]!.pubkey
+          -- Stub: eth_aggregate_pubkeys needs BLS12-381 point addition (see Crypto.lean)
+          aggregatePubkey := ByteArray.mk (Array.replicate 48 0)
+        }
+      }
   else state
 
 end Eth2
@@ -1,7 +1,8 @@
 /-
   State Transition — Top-level
 
-  Implements state_transition, process_slots, process_slot.
+  Implements state_transition, process_slots, process_slot, and chained
+  application of several blocks.
   Reference: https://eth2book.info/latest/part3/transition/
 -/
 import Guest.Eth2.Helpers
@@ -52,14 +53,28 @@ partial def processSlots (state : BeaconState) (targetSlot : Slot) : STFResult B
 
 -- Full state transition: advance slots + process block
 def stateTransition (state : BeaconState) (signedBlock : SignedBeaconBlock)
-    (_validateResult : Bool := true) : STFResult BeaconState := do
+    (validateResult : Bool := true) : Except STFError BeaconState := do
   let block := signedBlock.message
+  -- The check needs the real hash_tree_root, which is stubbed: refuse it rather than
+  -- reject every block for a root mismatch
+  if validateResult then
+    throw { stage := .unsupported,
+            reason := "state root validation needs hash_tree_root, which this guest stubs" }
   -- Process slots up to the block's slot
-  let state ← processSlots state block.slot
+  let state ← (processSlots state block.slot).atStage .slots
   -- Stub: skip block signature verification
   -- Process the block
   let state ← processBlock state block
-  -- Stub: skip state root verification (would compare block.stateRoot with hashTreeRoot(state))
-  .ok state
+  return state
+
+-- Apply a chain of blocks in order. On failure, returns the failing block's index
+-- (the number of blocks applied before it) with its error.
+def stateTransitionChain (state : BeaconState) (blocks : Array SignedBeaconBlock)
+    (validateResult : Bool := true) : Except (Nat × STFError) BeaconState := do
+  let (state, _) ← blocks.foldlM (init := (state, 0)) fun (state, applied) signedBlock =>
+    match stateTransition state signedBlock validateResult with
+    | .ok state => .ok (state, applied + 1)
+    | .error err => .error (applied, err)
+  return state
 
 end Eth2
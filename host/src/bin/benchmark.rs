@@ -1,11 +1,37 @@
 use clap::{Parser, ValueEnum};
+use eth2_journal::{
+    Eth2AggregateInput, Eth2AggregateJournal, Eth2Journal, Eth2Status, Root, POST_STATE_FD,
+};
+use host::eth2_testdata::{self, SignatureCheck};
+use host::profile::{Category, CycleProfile};
+use host::receipt_file::{self, ReceiptHeader};
+use host::{Guest, ReceiptKind};
 use methods::{
-    GUEST_ETH2_INIT_ELF, GUEST_ETH2_INIT_ID, GUEST_ETH2_NOINIT_ELF, GUEST_ETH2_NOINIT_ID,
-    GUEST_RUST_ELF, GUEST_RUST_ETH2_ELF, GUEST_RUST_ETH2_ID, GUEST_RUST_ID, METHOD_ELF,
-    METHOD_ID,
+    GUEST_ETH2_AGGREGATE_ELF, GUEST_ETH2_AGGREGATE_ID, GUEST_ETH2_INIT_ELF,
+    GUEST_ETH2_INIT_PRUNED_ELF, GUEST_ETH2_NOINIT_ELF, GUEST_ETH2_SNAPSHOT_APPLIED,
+    GUEST_ETH2_SNAPSHOT_ELF, GUEST_RUST_ELF, GUEST_RUST_ETH2_ELF, GUEST_RUST_ETH2_ID, METHOD_ELF,
 };
-use risc0_zkvm::{default_executor, default_prover, sha::Digest, ExecutorEnv};
-use std::time::Instant;
+use risc0_zkvm::{default_executor, default_prover, ExecutorEnv, ProveInfo, ProverOpts, Receipt};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::{fs, io, process};
+
+// Set when --format json/csv writes to stdout; the human-readable table then goes to stderr
+static TABLE_ON_STDERR: AtomicBool = AtomicBool::new(false);
+
+macro_rules! table {
+    ($($arg:tt)*) => {
+        if TABLE_ON_STDERR.load(Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
 
 #[derive(Parser)]
 #[command(name = "benchmark", about = "Benchmark Lean vs Rust guest in risc0 zkVM")]
@@ -26,9 +52,102 @@ struct Cli {
     #[arg(long, default_value_t = 3)]
     runs: usize,
 
+    /// Untimed runs of each guest before the timed ones
+    #[arg(long, default_value_t = 0)]
+    warmup: usize,
+
     /// Which guest to benchmark
     #[arg(long, default_value = "both")]
     guest: GuestChoice,
+
+    /// Check block.state_root against hash_tree_root(post_state) inside the eth2 guest.
+    /// Rust guest only: the Lean guests stub hash_tree_root and refuse the check.
+    #[arg(long)]
+    validate_state_root: bool,
+
+    /// Signature checks to run for real in the Rust eth2 guest (comma-separated); the rest stay stubbed.
+    /// Rust guest only: the Lean guests stub BLS and refuse the checks.
+    #[arg(long, value_delimiter = ',')]
+    verify_signatures: Vec<SignatureCheck>,
+
+    /// Pre-state shape and slot advance for the eth2 suite
+    #[arg(long, value_enum, default_value_t)]
+    scenario: eth2_testdata::Scenario,
+
+    /// Number of consecutive blocks chained into one eth2 input (and one proof)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    blocks: u32,
+
+    /// Prove each of the --blocks blocks as its own receipt, in parallel, and compose them
+    /// into one proof with the aggregation guest (eth2 suite, prove mode, Rust guest only)
+    #[arg(long)]
+    aggregate: bool,
+
+    /// Profile mode: execute each guest once with the zkVM profiler, write the pprof profile
+    /// to this directory as `<guest>-<N>.pb` and print cycles per function and category
+    /// instead of the timing table (execute mode)
+    #[arg(long, conflicts_with = "aggregate")]
+    profile: Option<PathBuf>,
+
+    /// Number of functions listed per guest in profile mode
+    #[arg(long, default_value_t = 20)]
+    profile_top: usize,
+
+    /// Receipt kind produced in prove mode; succinct compresses each composite receipt
+    /// and reports the compression time
+    #[arg(long, value_enum, default_value_t)]
+    receipt_kind: ReceiptKind,
+
+    /// Output format for results; json and csv include every field and run metadata
+    /// (--aggregate and --profile only print tables, so they refuse json and csv)
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
+
+    /// Write json/csv results to this file instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Compare against a previous `--format json` run and exit with status 3 if any
+    /// result regressed beyond the thresholds (not with --aggregate or --profile)
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Allowed increase in user/total cycles and segments over the baseline, in percent.
+    /// Cycle counts are deterministic, so the default flags any increase.
+    #[arg(long, default_value_t = 0.0)]
+    cycle_threshold: f64,
+
+    /// Allowed increase in median wall time over the baseline, in percent
+    #[arg(long, default_value_t = 10.0)]
+    time_threshold: f64,
+
+    /// Write every proven receipt to this directory as `<guest>-<N>.receipt`, in the
+    /// file format `host verify` reads (prove mode)
+    #[arg(long)]
+    save_receipts: Option<PathBuf>,
+
+    /// Have the eth2 guests write the full post-state to the private output channel,
+    /// and compare it across guests alongside the journal. The Lean guests commit zero
+    /// roots, so without it Lean and Rust are only compared on status and slot.
+    #[arg(long)]
+    emit_post_state: bool,
+}
+
+impl Cli {
+    fn runs(&self) -> Runs {
+        Runs {
+            warmup: self.warmup,
+            timed: self.runs,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
 }
 
 #[derive(Clone, ValueEnum)]
@@ -52,12 +171,66 @@ enum GuestChoice {
     LeanNoinit,
     /// Lean guest with Init library (eth2 only)
     LeanInit,
-    /// All eth2 guests (lean-noinit + lean-init + rust)
+    /// Lean guest with Init, initializing only the modules it reaches (eth2 only)
+    LeanPruned,
+    /// Lean guest with Init, starting from a heap snapshot taken after initialization (eth2 only)
+    LeanSnapshot,
+    /// All eth2 guests (lean-noinit + lean-init + lean-pruned + lean-snapshot + rust)
     All,
 }
 
 // ── Common result types ─────────────────────────
 
+/// How many times each guest runs: untimed warmups, then the timed runs
+#[derive(Clone, Copy)]
+struct Runs {
+    warmup: usize,
+    timed: usize,
+}
+
+/// Summary of the wall times of the timed runs that completed
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct WallStats {
+    mean_wall_ms: f64,
+    /// Sample standard deviation; 0 for a single run
+    stddev_wall_ms: f64,
+    min_wall_ms: u64,
+    median_wall_ms: u64,
+    p90_wall_ms: u64,
+    p99_wall_ms: u64,
+    max_wall_ms: u64,
+}
+
+impl WallStats {
+    fn new(wall_times_ms: &[u128]) -> Self {
+        // u64 rather than u128: serde cannot buffer u128 in a flattened struct
+        let mut sorted: Vec<u64> = wall_times_ms.iter().map(|&t| t as u64).collect();
+        sorted.sort();
+        let len = sorted.len();
+        if len == 0 {
+            return WallStats::default();
+        }
+        let mean = sorted.iter().sum::<u64>() as f64 / len as f64;
+        let variance = if len > 1 {
+            sorted.iter().map(|&t| (t as f64 - mean).powi(2)).sum::<f64>() / (len - 1) as f64
+        } else {
+            0.0
+        };
+        // Nearest rank: with few runs p90 and p99 are the max
+        let percentile = |p: usize| sorted[(p * len).div_ceil(100).clamp(1, len) - 1];
+        WallStats {
+            mean_wall_ms: mean,
+            stddev_wall_ms: variance.sqrt(),
+            min_wall_ms: sorted[0],
+            median_wall_ms: sorted[len / 2],
+            p90_wall_ms: percentile(90),
+            p99_wall_ms: percentile(99),
+            max_wall_ms: sorted[len - 1],
+        }
+    }
+}
+
 struct BenchResult {
     guest_name: &'static str,
     input: u32,
@@ -66,55 +239,161 @@ struct BenchResult {
     total_cycles: Option<u64>,
     paging_cycles: Option<u64>,
     segments: usize,
+    /// Prove mode: compression time of the first run (None for composite receipts)
+    compress_ms: Option<u128>,
+    /// Prove mode: encoded size of the final receipt
+    receipt_bytes: Option<u64>,
     wall_times_ms: Vec<u128>,
+    /// Warmups that failed, as "warmup N: ...", then repeat runs that failed (and are
+    /// missing from wall_times_ms) or committed a different journal than the first run,
+    /// as "run N: ..."
+    run_issues: Vec<String>,
 }
 
 impl BenchResult {
-    fn median_wall_ms(&self) -> u128 {
-        let mut sorted = self.wall_times_ms.clone();
-        sorted.sort();
-        sorted[sorted.len() / 2]
+    fn wall_stats(&self) -> WallStats {
+        WallStats::new(&self.wall_times_ms)
     }
 }
 
-/// Eth2 benchmark result (output is bytes, not u32)
+/// Eth2 benchmark result (output is the decoded journal, not u32)
 struct Eth2BenchResult {
     guest_name: &'static str,
     num_validators: u32,
-    output_bytes: Vec<u8>,
+    /// None if the guest crashed or committed an undecodable journal
+    journal: Option<Eth2Journal>,
+    journal_bytes: usize,
+    /// Serialized post-state from the private output channel (empty unless requested)
+    post_state: Vec<u8>,
     user_cycles: u64,
     total_cycles: Option<u64>,
     paging_cycles: Option<u64>,
     segments: usize,
+    /// Prove mode: compression time of the first run (None for composite receipts)
+    compress_ms: Option<u128>,
+    /// Prove mode: encoded size of the final receipt (None if compression failed)
+    receipt_bytes: Option<u64>,
     wall_times_ms: Vec<u128>,
+    /// Warmups that failed, as "warmup N: ...", then a first-run receipt that failed to
+    /// verify or compress, then repeat runs that failed (and are missing from wall_times_ms) or
+    /// committed a different journal than the first run, as "run N: ..."
+    run_issues: Vec<String>,
 }
 
 impl Eth2BenchResult {
-    fn median_wall_ms(&self) -> u128 {
-        let mut sorted = self.wall_times_ms.clone();
-        sorted.sort();
-        sorted[sorted.len() / 2]
+    fn wall_stats(&self) -> WallStats {
+        WallStats::new(&self.wall_times_ms)
     }
 
-    /// Check if the execution failed or returned an error marker
+    /// Check if the execution failed or the guest reported an error status
     fn is_error(&self) -> bool {
-        self.output_bytes.is_empty() || self.output_bytes.len() == 1
+        self.journal.as_ref().is_none_or(|j| j.status != Eth2Status::Ok)
+    }
+
+    fn error_description(&self) -> String {
+        let Some(journal) = &self.journal else {
+            return "CRASHED".to_string();
+        };
+        match &journal.status {
+            Eth2Status::Ok => "OK".to_string(),
+            // Name the failing block once earlier blocks of a chain went through
+            Eth2Status::Error(e) if journal.blocks_applied > 0 => {
+                format!("block {}: {}", journal.blocks_applied, e)
+            }
+            Eth2Status::Error(e) => e.to_string(),
+        }
     }
+}
+
+// Lean guests commit the zero placeholder root until their hash_tree_root is real
+fn roots_comparable(a: &Root, b: &Root) -> bool {
+    *a != [0; 32] && *b != [0; 32]
+}
+
+/// How two guests' outputs compare
+enum Parity {
+    /// Status, slot and blocks_applied agree, as do the listed roots and post-state;
+    /// empty when neither side had a root or post-state to compare
+    Match(Vec<&'static str>),
+    /// First field that differs
+    Mismatch(&'static str),
+}
 
-    fn error_description(&self) -> &'static str {
-        if self.output_bytes.is_empty() {
-            return "CRASHED";
+fn compare_outputs(a: &Eth2BenchResult, b: &Eth2BenchResult) -> Parity {
+    let (ja, jb) = match (&a.journal, &b.journal) {
+        (Some(ja), Some(jb)) => (ja, jb),
+        (None, None) => return Parity::Match(vec![]),
+        _ => return Parity::Mismatch("journal"),
+    };
+    if ja.status != jb.status {
+        return Parity::Mismatch("status");
+    }
+    if ja.slot != jb.slot {
+        return Parity::Mismatch("slot");
+    }
+    if ja.blocks_applied != jb.blocks_applied {
+        return Parity::Mismatch("blocks_applied");
+    }
+    let roots = [
+        ("pre_state_root", &ja.pre_state_root, &jb.pre_state_root),
+        ("block_root", &ja.block_root, &jb.block_root),
+        ("post_state_root", &ja.post_state_root, &jb.post_state_root),
+    ];
+    let mut compared = Vec::new();
+    for (name, ra, rb) in roots {
+        if roots_comparable(ra, rb) {
+            if ra != rb {
+                return Parity::Mismatch(name);
+            }
+            compared.push(name);
         }
-        if self.output_bytes.len() != 1 {
-            return "OK";
+    }
+    if !a.post_state.is_empty() && !b.post_state.is_empty() {
+        if a.post_state != b.post_state {
+            return Parity::Mismatch("post-state");
         }
-        match self.output_bytes[0] {
-            0xFF => "State decode error",
-            0xFE => "Block decode error",
-            0xFD => "STF error",
-            _ => "Unknown error",
+        compared.push("post-state");
+    }
+    Parity::Match(compared)
+}
+
+// ── Repeat runs ─────────────────────────────────
+
+/// A guest run for the warmup and repeat loops: its wall time and committed journal
+type TimedRun<'a> = dyn FnMut() -> anyhow::Result<(u128, Vec<u8>)> + 'a;
+
+/// Untimed runs before the timed ones. Returns the warmups that failed, as "warmup N: ...".
+fn warm_up(runs: Runs, run: &mut TimedRun) -> Vec<String> {
+    let mut issues = Vec::new();
+    for n in 1..=runs.warmup {
+        if let Err(e) = run() {
+            issues.push(format!("warmup {}: FAILED: {}", n, e));
         }
     }
+    issues
+}
+
+/// Timed runs 2..=N after the first one, which committed `first_journal`. Returns the
+/// runs that failed, which get no wall time, or committed a different journal.
+fn repeat_runs(
+    runs: Runs,
+    first_journal: &[u8],
+    wall_times: &mut Vec<u128>,
+    run: &mut TimedRun,
+) -> Vec<String> {
+    let mut issues = Vec::new();
+    for n in 2..=runs.timed {
+        match run() {
+            Ok((wall_ms, journal)) => {
+                wall_times.push(wall_ms);
+                if journal != first_journal {
+                    issues.push(format!("run {}: journal differs from run 1", n));
+                }
+            }
+            Err(e) => issues.push(format!("run {}: FAILED: {}", n, e)),
+        }
+    }
+    issues
 }
 
 // ── Sum benchmark functions (existing) ──────────
@@ -127,8 +406,15 @@ fn build_env(input: u32) -> ExecutorEnv<'static> {
         .unwrap()
 }
 
-fn bench_execute(elf: &[u8], input: u32, runs: usize, guest_name: &'static str) -> BenchResult {
+fn bench_execute(elf: &[u8], input: u32, runs: Runs, guest_name: &'static str) -> BenchResult {
     let executor = default_executor();
+    let mut run = || {
+        let env = build_env(input);
+        let start = Instant::now();
+        let session = executor.execute(env, elf)?;
+        Ok((start.elapsed().as_millis(), session.journal.bytes))
+    };
+    let mut run_issues = warm_up(runs, &mut run);
 
     let env = build_env(input);
     let start = Instant::now();
@@ -140,12 +426,12 @@ fn bench_execute(elf: &[u8], input: u32, runs: usize, guest_name: &'static str)
     let output: u32 = session.journal.decode().unwrap();
 
     let mut wall_times = vec![first_wall];
-    for _ in 1..runs {
-        let env = build_env(input);
-        let start = Instant::now();
-        let _ = executor.execute(env, elf).unwrap();
-        wall_times.push(start.elapsed().as_millis());
-    }
+    run_issues.extend(repeat_runs(
+        runs,
+        &session.journal.bytes,
+        &mut wall_times,
+        &mut run,
+    ));
 
     BenchResult {
         guest_name,
@@ -155,18 +441,40 @@ fn bench_execute(elf: &[u8], input: u32, runs: usize, guest_name: &'static str)
         total_cycles: None,
         paging_cycles: None,
         segments,
+        compress_ms: None,
+        receipt_bytes: None,
         wall_times_ms: wall_times,
+        run_issues,
+    }
+}
+
+/// Write a receipt under --save-receipts; `input` is what the receipt file hashes
+fn save_receipt(dir: Option<&Path>, guest: Guest, n: u32, input: &[u8], receipt: &Receipt) {
+    let Some(dir) = dir else { return };
+    let path = dir.join(format!("{}-{}.receipt", guest.name(), n));
+    let header = ReceiptHeader::new(guest.name(), guest.image_id(), input);
+    if let Err(e) = receipt_file::write(&path, &header, receipt) {
+        eprintln!("  {} failed to save receipt: {:#}", guest.name(), e);
     }
 }
 
 fn bench_prove(
-    elf: &[u8],
-    id: impl Into<Digest>,
+    guest: Guest,
     input: u32,
-    runs: usize,
+    runs: Runs,
     guest_name: &'static str,
+    receipt_kind: ReceiptKind,
+    save_dir: Option<&Path>,
 ) -> BenchResult {
     let prover = default_prover();
+    let elf = guest.elf();
+    let mut run = || {
+        let env = build_env(input);
+        let start = Instant::now();
+        let prove_info = prover.prove(env, elf)?;
+        Ok((start.elapsed().as_millis(), prove_info.receipt.journal.bytes))
+    };
+    let mut run_issues = warm_up(runs, &mut run);
 
     let env = build_env(input);
     let start = Instant::now();
@@ -178,10 +486,22 @@ fn bench_prove(
 
     prove_info
         .receipt
-        .verify(id)
+        .verify(guest.image_id())
         .expect("Receipt verification failed");
+    let (receipt, compress_time) = receipt_kind
+        .compress(prover.as_ref(), prove_info.receipt.clone())
+        .expect("Receipt compression failed");
+    save_receipt(save_dir, guest, input, &input.to_le_bytes(), &receipt);
+
+    let mut wall_times = vec![first_wall];
+    run_issues.extend(repeat_runs(
+        runs,
+        &receipt.journal.bytes,
+        &mut wall_times,
+        &mut run,
+    ));
 
-    let result = BenchResult {
+    BenchResult {
         guest_name,
         input,
         output,
@@ -189,263 +509,25 @@ fn bench_prove(
         total_cycles: Some(stats.total_cycles),
         paging_cycles: Some(stats.paging_cycles),
         segments: stats.segments,
-        wall_times_ms: vec![first_wall],
-    };
-
-    if runs > 1 {
-        let mut wall_times = result.wall_times_ms.clone();
-        for _ in 1..runs {
-            let env = build_env(input);
-            let start = Instant::now();
-            let _ = prover.prove(env, elf).unwrap();
-            wall_times.push(start.elapsed().as_millis());
-        }
-        return BenchResult {
-            wall_times_ms: wall_times,
-            ..result
-        };
+        compress_ms: compress_time.map(|t| t.as_millis()),
+        receipt_bytes: Some(host::receipt_size(&receipt)),
+        wall_times_ms: wall_times,
+        run_issues,
     }
-
-    result
 }
 
 // ── Eth2 test data builder ──────────────────────
 
-mod eth2_testdata {
-    //! Builds minimal BeaconState + SignedBeaconBlock for testing.
-    //! Uses the same binary format as the Lean/Rust guest serializers.
-
-    const FAR_FUTURE_EPOCH: u64 = u64::MAX;
-
-    fn write_u64(buf: &mut Vec<u8>, v: u64) {
-        buf.extend_from_slice(&v.to_le_bytes());
-    }
-    fn write_u32(buf: &mut Vec<u8>, v: u32) {
-        buf.extend_from_slice(&v.to_le_bytes());
-    }
-    fn write_u8(buf: &mut Vec<u8>, v: u8) {
-        buf.push(v);
-    }
-    fn write_bool(buf: &mut Vec<u8>, v: bool) {
-        buf.push(if v { 1 } else { 0 });
-    }
-    fn write_bytes(buf: &mut Vec<u8>, v: &[u8]) {
-        write_u32(buf, v.len() as u32);
-        buf.extend_from_slice(v);
-    }
-
-    fn zero_bytes(n: usize) -> Vec<u8> {
-        vec![0u8; n]
-    }
-
-    /// Build serialized test input: BeaconState + SignedBeaconBlock
-    ///
-    /// Creates a minimal but valid state at slot 100 with `num_validators` validators,
-    /// and a block for slot 101 (simple 1-slot advance, no epoch boundary).
-    pub fn build_test_input(num_validators: usize) -> Vec<u8> {
-        let mut buf = Vec::new();
-
-        // ── BeaconState ──
-
-        // genesis_time
-        write_u64(&mut buf, 1_000_000);
-        // genesis_validators_root
-        write_bytes(&mut buf, &zero_bytes(32));
-        // slot
-        write_u64(&mut buf, 100);
-
-        // fork: previous_version, current_version, epoch
-        write_bytes(&mut buf, &[0, 0, 0, 0]);
-        write_bytes(&mut buf, &[1, 0, 0, 0]);
-        write_u64(&mut buf, 0);
-
-        // latest_block_header: slot, proposer_index, parent_root, state_root, body_root
-        write_u64(&mut buf, 100);
-        write_u64(&mut buf, 0);
-        write_bytes(&mut buf, &zero_bytes(32));
-        write_bytes(&mut buf, &zero_bytes(32));
-        write_bytes(&mut buf, &zero_bytes(32));
-
-        // block_roots: 200 entries (enough for slot 101 % 8192 = 101)
-        let roots_len = 200;
-        write_u32(&mut buf, roots_len);
-        for _ in 0..roots_len {
-            write_bytes(&mut buf, &zero_bytes(32));
-        }
-
-        // state_roots: 200 entries
-        write_u32(&mut buf, roots_len);
-        for _ in 0..roots_len {
-            write_bytes(&mut buf, &zero_bytes(32));
-        }
-
-        // historical_roots: empty
-        write_u32(&mut buf, 0);
-
-        // eth1_data: deposit_root, deposit_count, block_hash
-        write_bytes(&mut buf, &zero_bytes(32));
-        write_u64(&mut buf, 0);
-        write_bytes(&mut buf, &zero_bytes(32));
-
-        // eth1_data_votes: empty
-        write_u32(&mut buf, 0);
-
-        // eth1_deposit_index
-        write_u64(&mut buf, 0);
-
-        // validators
-        let n = num_validators as u32;
-        write_u32(&mut buf, n);
-        for i in 0..num_validators {
-            // pubkey: 48 bytes (unique per validator)
-            let mut pk = vec![0u8; 48];
-            pk[0] = i as u8;
-            pk[1] = (i >> 8) as u8;
-            write_bytes(&mut buf, &pk);
-            // withdrawal_credentials: 32 bytes
-            write_bytes(&mut buf, &zero_bytes(32));
-            // effective_balance: 32 ETH
-            write_u64(&mut buf, 32_000_000_000);
-            // slashed: false
-            write_bool(&mut buf, false);
-            // activation_eligibility_epoch: 0
-            write_u64(&mut buf, 0);
-            // activation_epoch: 0
-            write_u64(&mut buf, 0);
-            // exit_epoch: FAR_FUTURE_EPOCH
-            write_u64(&mut buf, FAR_FUTURE_EPOCH);
-            // withdrawable_epoch: FAR_FUTURE_EPOCH
-            write_u64(&mut buf, FAR_FUTURE_EPOCH);
-        }
-
-        // balances: 32 ETH each
-        write_u32(&mut buf, n);
-        for _ in 0..num_validators {
-            write_u64(&mut buf, 32_000_000_000);
-        }
-
-        // randao_mixes: 200 entries (enough for epoch 3 % 65536 = 3)
-        let mixes_len = 200u32;
-        write_u32(&mut buf, mixes_len);
-        for _ in 0..mixes_len {
-            write_bytes(&mut buf, &zero_bytes(32));
-        }
-
-        // slashings: 200 entries
-        let slashings_len = 200u32;
-        write_u32(&mut buf, slashings_len);
-        for _ in 0..slashings_len {
-            write_u64(&mut buf, 0);
-        }
-
-        // previous_epoch_participation: all flags set (0x07)
-        write_u32(&mut buf, n);
-        for _ in 0..num_validators {
-            write_u8(&mut buf, 0x07);
-        }
-
-        // current_epoch_participation: all flags set
-        write_u32(&mut buf, n);
-        for _ in 0..num_validators {
-            write_u8(&mut buf, 0x07);
-        }
-
-        // justification_bits: 4 bytes
-        write_bytes(&mut buf, &[0, 0, 0, 0]);
-
-        // previous_justified_checkpoint: epoch + root
-        write_u64(&mut buf, 0);
-        write_bytes(&mut buf, &zero_bytes(32));
-
-        // current_justified_checkpoint
-        write_u64(&mut buf, 0);
-        write_bytes(&mut buf, &zero_bytes(32));
-
-        // finalized_checkpoint
-        write_u64(&mut buf, 0);
-        write_bytes(&mut buf, &zero_bytes(32));
-
-        // inactivity_scores
-        write_u32(&mut buf, n);
-        for _ in 0..num_validators {
-            write_u64(&mut buf, 0);
-        }
-
-        // current_sync_committee: 512 pubkeys + aggregate_pubkey
-        let sync_committee_size = 512u32;
-        write_u32(&mut buf, sync_committee_size);
-        for _ in 0..sync_committee_size {
-            write_bytes(&mut buf, &zero_bytes(48));
-        }
-        write_bytes(&mut buf, &zero_bytes(48));
-
-        // next_sync_committee
-        write_u32(&mut buf, sync_committee_size);
-        for _ in 0..sync_committee_size {
-            write_bytes(&mut buf, &zero_bytes(48));
-        }
-        write_bytes(&mut buf, &zero_bytes(48));
-
-        // latest_execution_payload_header (15 fields)
-        write_bytes(&mut buf, &zero_bytes(32)); // parent_hash
-        write_bytes(&mut buf, &zero_bytes(20)); // fee_recipient
-        write_bytes(&mut buf, &zero_bytes(32)); // state_root
-        write_bytes(&mut buf, &zero_bytes(32)); // receipts_root
-        write_bytes(&mut buf, &zero_bytes(256)); // logs_bloom
-        write_bytes(&mut buf, &zero_bytes(32)); // prev_randao
-        write_u64(&mut buf, 0); // block_number
-        write_u64(&mut buf, 0); // gas_limit
-        write_u64(&mut buf, 0); // gas_used
-        write_u64(&mut buf, 0); // timestamp
-        write_bytes(&mut buf, &[]); // extra_data (empty)
-        write_u64(&mut buf, 0); // base_fee_per_gas
-        write_bytes(&mut buf, &zero_bytes(32)); // block_hash
-        write_bytes(&mut buf, &zero_bytes(32)); // transactions_root
-        write_bytes(&mut buf, &zero_bytes(32)); // withdrawals_root
-
-        // next_withdrawal_index
-        write_u64(&mut buf, 0);
-        // next_withdrawal_validator_index
-        write_u64(&mut buf, 0);
-
-        // historical_summaries: empty
-        write_u32(&mut buf, 0);
-
-        // ── SignedBeaconBlock ──
-
-        // slot: 101 (one slot ahead of state.slot=100)
-        write_u64(&mut buf, 101);
-        // proposer_index: must match getBeaconProposerIndex stub (slot % validator_count)
-        // slot=101, validators=N, so proposer_index = 101 % N
-        write_u64(&mut buf, 101 % num_validators as u64);
-        // parent_root
-        write_bytes(&mut buf, &zero_bytes(32));
-        // state_root
-        write_bytes(&mut buf, &zero_bytes(32));
-        // randao_reveal
-        write_bytes(&mut buf, &zero_bytes(96));
-        // eth1_data: deposit_root, deposit_count, block_hash
-        write_bytes(&mut buf, &zero_bytes(32));
-        write_u64(&mut buf, 0);
-        write_bytes(&mut buf, &zero_bytes(32));
-        // graffiti
-        write_bytes(&mut buf, &zero_bytes(32));
-        // op_count (0 = no operations)
-        write_u32(&mut buf, 0);
-        // signature
-        write_bytes(&mut buf, &zero_bytes(96));
-
-        buf
-    }
-}
 
 // ── Eth2 benchmark functions ────────────────────
 
-fn build_eth2_env(test_input: &[u8]) -> ExecutorEnv<'static> {
+/// Env for one eth2 run; the guest's private post-state output is captured in `post_state`
+fn build_eth2_env<'a>(test_input: &[u8], post_state: &'a mut Vec<u8>) -> ExecutorEnv<'a> {
     let input_vec = test_input.to_vec();
     ExecutorEnv::builder()
         .write(&input_vec)
         .unwrap()
+        .write_fd(POST_STATE_FD, post_state)
         .build()
         .unwrap()
 }
@@ -454,12 +536,21 @@ fn bench_eth2_execute(
     elf: &[u8],
     test_input: &[u8],
     num_validators: u32,
-    runs: usize,
+    runs: Runs,
     guest_name: &'static str,
 ) -> Eth2BenchResult {
     let executor = default_executor();
+    let mut run = || {
+        let mut scratch = Vec::new();
+        let env = build_eth2_env(test_input, &mut scratch);
+        let start = Instant::now();
+        let session = executor.execute(env, elf)?;
+        Ok((start.elapsed().as_millis(), session.journal.bytes))
+    };
+    let mut run_issues = warm_up(runs, &mut run);
 
-    let env = build_eth2_env(test_input);
+    let mut post_state = Vec::new();
+    let env = build_eth2_env(test_input, &mut post_state);
     let start = Instant::now();
     let result = executor.execute(env, elf);
     let first_wall = start.elapsed().as_millis();
@@ -468,26 +559,31 @@ fn bench_eth2_execute(
         Ok(session) => {
             let user_cycles = session.cycles();
             let segments = session.segments.len();
-            let output_bytes = session.journal.bytes.clone();
-
+            let journal = session.journal.decode().ok();
+            let journal_bytes = session.journal.bytes.len();
 
             let mut wall_times = vec![first_wall];
-            for _ in 1..runs {
-                let env = build_eth2_env(test_input);
-                let start = Instant::now();
-                let _ = executor.execute(env, elf);
-                wall_times.push(start.elapsed().as_millis());
-            }
+            run_issues.extend(repeat_runs(
+                runs,
+                &session.journal.bytes,
+                &mut wall_times,
+                &mut run,
+            ));
 
             Eth2BenchResult {
                 guest_name,
                 num_validators,
-                output_bytes,
+                journal,
+                journal_bytes,
+                post_state,
                 user_cycles,
                 total_cycles: None,
                 paging_cycles: None,
                 segments,
+                compress_ms: None,
+                receipt_bytes: None,
                 wall_times_ms: wall_times,
+                run_issues,
             }
         }
         Err(e) => {
@@ -495,29 +591,45 @@ fn bench_eth2_execute(
             Eth2BenchResult {
                 guest_name,
                 num_validators,
-                output_bytes: vec![],
+                journal: None,
+                journal_bytes: 0,
+                post_state: vec![],
                 user_cycles: 0,
                 total_cycles: None,
                 paging_cycles: None,
                 segments: 0,
-                wall_times_ms: vec![first_wall],
+                compress_ms: None,
+                receipt_bytes: None,
+                // A failed run's time is not a measurement
+                wall_times_ms: Vec::new(),
+                run_issues,
             }
         }
     }
 }
 
 fn bench_eth2_prove(
-    elf: &[u8],
-    id: impl Into<Digest>,
+    guest: Guest,
     test_input: &[u8],
     num_validators: u32,
-    runs: usize,
+    runs: Runs,
     guest_name: &'static str,
+    receipt_kind: ReceiptKind,
+    save_dir: Option<&Path>,
 ) -> Eth2BenchResult {
     let prover = default_prover();
-    let digest = id.into();
+    let elf = guest.elf();
+    let mut run = || {
+        let mut scratch = Vec::new();
+        let env = build_eth2_env(test_input, &mut scratch);
+        let start = Instant::now();
+        let prove_info = prover.prove(env, elf)?;
+        Ok((start.elapsed().as_millis(), prove_info.receipt.journal.bytes))
+    };
+    let mut run_issues = warm_up(runs, &mut run);
 
-    let env = build_eth2_env(test_input);
+    let mut post_state = Vec::new();
+    let env = build_eth2_env(test_input, &mut post_state);
     let start = Instant::now();
     let result = prover.prove(env, elf);
     let first_wall = start.elapsed().as_millis();
@@ -525,55 +637,222 @@ fn bench_eth2_prove(
     match result {
         Ok(prove_info) => {
             let stats = &prove_info.stats;
-            let output_bytes = prove_info.receipt.journal.bytes.clone();
-
-            if let Err(e) = prove_info.receipt.verify(digest) {
-                eprintln!("  {} receipt verification failed: {}", guest_name, e);
+            let journal = prove_info.receipt.journal.decode().ok();
+            let journal_bytes = prove_info.receipt.journal.bytes.len();
+
+            // An unverified receipt is never saved: other machines trust the files
+            let verified = match prove_info.receipt.verify(guest.image_id()) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("  {} receipt verification failed: {}", guest_name, e);
+                    run_issues.push(format!("run 1: receipt verification failed: {}", e));
+                    false
+                }
+            };
+            // Without a receipt of the requested kind there is no size or file to report
+            let compressed = match receipt_kind.compress(prover.as_ref(), prove_info.receipt.clone()) {
+                Ok(compressed) => Some(compressed),
+                Err(e) => {
+                    eprintln!("  {} receipt compression failed: {}", guest_name, e);
+                    run_issues.push(format!("run 1: receipt compression failed: {}", e));
+                    None
+                }
+            };
+            if let (true, Some((receipt, _))) = (verified, &compressed) {
+                save_receipt(save_dir, guest, num_validators, test_input, receipt);
             }
 
-            let result = Eth2BenchResult {
+            let mut wall_times = vec![first_wall];
+            run_issues.extend(repeat_runs(
+                runs,
+                &prove_info.receipt.journal.bytes,
+                &mut wall_times,
+                &mut run,
+            ));
+
+            Eth2BenchResult {
                 guest_name,
                 num_validators,
-                output_bytes,
+                journal,
+                journal_bytes,
+                post_state,
                 user_cycles: stats.user_cycles,
                 total_cycles: Some(stats.total_cycles),
                 paging_cycles: Some(stats.paging_cycles),
                 segments: stats.segments,
-                wall_times_ms: vec![first_wall],
-            };
-
-            if runs > 1 {
-                let mut wall_times = result.wall_times_ms.clone();
-                for _ in 1..runs {
-                    let env = build_eth2_env(test_input);
-                    let start = Instant::now();
-                    let _ = prover.prove(env, elf);
-                    wall_times.push(start.elapsed().as_millis());
-                }
-                return Eth2BenchResult {
-                    wall_times_ms: wall_times,
-                    ..result
-                };
+                compress_ms: compressed
+                    .as_ref()
+                    .and_then(|(_, time)| time.map(|t| t.as_millis())),
+                receipt_bytes: compressed
+                    .as_ref()
+                    .map(|(receipt, _)| host::receipt_size(receipt)),
+                wall_times_ms: wall_times,
+                run_issues,
             }
-
-            result
         }
         Err(e) => {
             eprintln!("  {} PROVE FAILED: {}", guest_name, e);
             Eth2BenchResult {
                 guest_name,
                 num_validators,
-                output_bytes: vec![],
+                journal: None,
+                journal_bytes: 0,
+                post_state: vec![],
                 user_cycles: 0,
                 total_cycles: None,
                 paging_cycles: None,
                 segments: 0,
-                wall_times_ms: vec![first_wall],
+                compress_ms: None,
+                receipt_bytes: None,
+                // A failed run's time is not a measurement
+                wall_times_ms: Vec::new(),
+                run_issues,
             }
         }
     }
 }
 
+/// Per-block receipts proven in parallel and composed by the aggregation guest
+struct Eth2AggregateResult {
+    guest_name: &'static str,
+    num_validators: u32,
+    /// Aggregate journal, or why a block proof or the aggregation failed
+    outcome: Result<Eth2AggregateJournal, String>,
+    /// Summed over the per-block proofs
+    block_user_cycles: u64,
+    aggregate_user_cycles: u64,
+    segments: usize,
+    blocks_wall_ms: u128,
+    aggregate_wall_ms: u128,
+}
+
+// Prove one receipt per input on a pool of worker threads; results come back in input order
+fn prove_block_receipts(
+    elf: &[u8],
+    inputs: &[Vec<u8>],
+    opts: &ProverOpts,
+) -> Result<Vec<ProveInfo>, String> {
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(inputs.len());
+    let mut results: Vec<Option<Result<ProveInfo, String>>> =
+        (0..inputs.len()).map(|_| None).collect();
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut proven = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= inputs.len() {
+                            break proven;
+                        }
+                        let mut post_state = Vec::new();
+                        let env = build_eth2_env(&inputs[i], &mut post_state);
+                        let info = default_prover()
+                            .prove_with_opts(env, elf, opts)
+                            .map_err(|e| format!("block {}: {}", i, e));
+                        proven.push((i, info));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            for (i, info) in handle.join().expect("prover thread panicked") {
+                results[i] = Some(info);
+            }
+        }
+    });
+    results
+        .into_iter()
+        .map(|r| r.expect("every input is proven"))
+        .collect()
+}
+
+/// Prove every block input with `elf`, then compose the receipts with the aggregation
+/// guest via add_assumption. Succinct receipts keep the final proof self-contained.
+fn bench_eth2_aggregate(
+    elf: &[u8],
+    image_id: [u32; 8],
+    inputs: &[Vec<u8>],
+    num_validators: u32,
+    guest_name: &'static str,
+) -> Eth2AggregateResult {
+    let opts = ProverOpts::succinct();
+
+    let start = Instant::now();
+    let blocks = prove_block_receipts(elf, inputs, &opts);
+    let mut result = Eth2AggregateResult {
+        guest_name,
+        num_validators,
+        outcome: Err(String::new()),
+        block_user_cycles: 0,
+        aggregate_user_cycles: 0,
+        segments: 0,
+        blocks_wall_ms: start.elapsed().as_millis(),
+        aggregate_wall_ms: 0,
+    };
+    let blocks = match blocks {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            result.outcome = Err(e);
+            return result;
+        }
+    };
+    result.block_user_cycles = blocks.iter().map(|b| b.stats.user_cycles).sum();
+    result.segments = blocks.iter().map(|b| b.stats.segments).sum();
+
+    let journals: Result<Vec<Eth2Journal>, _> =
+        blocks.iter().map(|b| b.receipt.journal.decode()).collect();
+    let journals = match journals {
+        Ok(journals) => journals,
+        Err(e) => {
+            result.outcome = Err(format!("undecodable block journal: {}", e));
+            return result;
+        }
+    };
+    for (i, journal) in journals.iter().enumerate() {
+        if let Eth2Status::Error(e) = &journal.status {
+            result.outcome = Err(format!("block {}: {}", i, e));
+            return result;
+        }
+    }
+
+    let mut builder = ExecutorEnv::builder();
+    builder
+        .write(&Eth2AggregateInput { image_id, journals })
+        .unwrap();
+    for block in blocks {
+        builder.add_assumption(block.receipt);
+    }
+    let env = builder.build().unwrap();
+
+    let start = Instant::now();
+    let aggregate = default_prover().prove_with_opts(env, GUEST_ETH2_AGGREGATE_ELF, &opts);
+    result.aggregate_wall_ms = start.elapsed().as_millis();
+
+    result.outcome = aggregate
+        .map_err(|e| format!("aggregation failed: {}", e))
+        .and_then(|info| {
+            result.aggregate_user_cycles = info.stats.user_cycles;
+            result.segments += info.stats.segments;
+            info.receipt
+                .verify(GUEST_ETH2_AGGREGATE_ID)
+                .map_err(|e| format!("aggregate receipt verification failed: {}", e))?;
+            let journal: Eth2AggregateJournal = info
+                .receipt
+                .journal
+                .decode()
+                .map_err(|e| format!("undecodable aggregate journal: {}", e))?;
+            if journal.image_id != image_id {
+                return Err("aggregate journal names another image ID".to_string());
+            }
+            Ok(journal)
+        });
+    result
+}
+
 // ── Formatting helpers ──────────────────────────
 
 fn format_number(n: u64) -> String {
@@ -596,21 +875,38 @@ fn format_time(ms: u128) -> String {
     }
 }
 
+// "-" when no compression ran (composite receipts)
+fn format_compress_time(ms: Option<u128>) -> String {
+    ms.map_or("-".to_string(), format_time)
+}
+
+fn format_receipt_size(bytes: Option<u64>) -> String {
+    bytes.map_or("-".to_string(), |b| format!("{}B", format_number(b)))
+}
+
 fn print_header(mode: &Mode) {
     match mode {
         Mode::Execute => {
-            println!(
+            table!(
                 "{:<14} {:>7} {:>14} {:>10} {:>10}",
                 "Guest", "N", "User Cycles", "Segments", "Time"
             );
-            println!("{}", "-".repeat(61));
+            table!("{}", "-".repeat(61));
         }
         Mode::Prove => {
-            println!(
-                "{:<14} {:>7} {:>14} {:>14} {:>14} {:>10} {:>10}",
-                "Guest", "N", "User Cycles", "Total Cycles", "Paging Cycles", "Segments", "Time"
+            table!(
+                "{:<14} {:>7} {:>14} {:>14} {:>14} {:>10} {:>10} {:>10} {:>12}",
+                "Guest",
+                "N",
+                "User Cycles",
+                "Total Cycles",
+                "Paging Cycles",
+                "Segments",
+                "Time",
+                "Compress",
+                "Receipt"
             );
-            println!("{}", "-".repeat(91));
+            table!("{}", "-".repeat(115));
         }
     }
 }
@@ -618,63 +914,96 @@ fn print_header(mode: &Mode) {
 fn print_result(result: &BenchResult, mode: &Mode) {
     match mode {
         Mode::Execute => {
-            println!(
+            table!(
                 "{:<14} {:>7} {:>14} {:>10} {:>10}",
                 result.guest_name,
                 result.input,
                 format_number(result.user_cycles),
                 result.segments,
-                format_time(result.median_wall_ms()),
+                format_time(result.wall_stats().median_wall_ms.into()),
             );
         }
         Mode::Prove => {
-            println!(
-                "{:<14} {:>7} {:>14} {:>14} {:>14} {:>10} {:>10}",
+            table!(
+                "{:<14} {:>7} {:>14} {:>14} {:>14} {:>10} {:>10} {:>10} {:>12}",
                 result.guest_name,
                 result.input,
                 format_number(result.user_cycles),
                 format_number(result.total_cycles.unwrap_or(0)),
                 format_number(result.paging_cycles.unwrap_or(0)),
                 result.segments,
-                format_time(result.median_wall_ms()),
+                format_time(result.wall_stats().median_wall_ms.into()),
+                format_compress_time(result.compress_ms),
+                format_receipt_size(result.receipt_bytes),
             );
         }
     }
+    print_run_details(&result.wall_times_ms, &result.run_issues);
+}
+
+/// Spread of the timed runs and any failed or diverging repeat, under a result row
+fn print_run_details(wall_times_ms: &[u128], run_issues: &[String]) {
+    if wall_times_ms.len() > 1 {
+        let stats = WallStats::new(wall_times_ms);
+        table!(
+            "{:>22} mean {:.1}ms ± {:.1}ms, min {}, p90 {}, p99 {}, max {} ({} runs)",
+            "",
+            stats.mean_wall_ms,
+            stats.stddev_wall_ms,
+            format_time(stats.min_wall_ms.into()),
+            format_time(stats.p90_wall_ms.into()),
+            format_time(stats.p99_wall_ms.into()),
+            format_time(stats.max_wall_ms.into()),
+            wall_times_ms.len()
+        );
+    }
+    for issue in run_issues {
+        table!("{:>22} {}", "", issue);
+    }
 }
 
 fn print_eth2_result(result: &Eth2BenchResult, mode: &Mode) {
     let status = if result.is_error() {
         format!("ERR:{}", result.error_description())
+    } else if result.post_state.is_empty() {
+        format!("{}B journal", format_number(result.journal_bytes as u64))
     } else {
-        format!("{}B", format_number(result.output_bytes.len() as u64))
+        format!(
+            "{}B journal, {}B post-state",
+            format_number(result.journal_bytes as u64),
+            format_number(result.post_state.len() as u64)
+        )
     };
 
     match mode {
         Mode::Execute => {
-            println!(
+            table!(
                 "{:<14} {:>7} {:>14} {:>10} {:>10}  [{}]",
                 result.guest_name,
                 result.num_validators,
                 format_number(result.user_cycles),
                 result.segments,
-                format_time(result.median_wall_ms()),
+                format_time(result.wall_stats().median_wall_ms.into()),
                 status,
             );
         }
         Mode::Prove => {
-            println!(
-                "{:<14} {:>7} {:>14} {:>14} {:>14} {:>10} {:>10}  [{}]",
+            table!(
+                "{:<14} {:>7} {:>14} {:>14} {:>14} {:>10} {:>10} {:>10} {:>12}  [{}]",
                 result.guest_name,
                 result.num_validators,
                 format_number(result.user_cycles),
                 format_number(result.total_cycles.unwrap_or(0)),
                 format_number(result.paging_cycles.unwrap_or(0)),
                 result.segments,
-                format_time(result.median_wall_ms()),
+                format_time(result.wall_stats().median_wall_ms.into()),
+                format_compress_time(result.compress_ms),
+                format_receipt_size(result.receipt_bytes),
                 status,
             );
         }
     }
+    print_run_details(&result.wall_times_ms, &result.run_issues);
 }
 
 fn print_ratio(lean: &BenchResult, rust: &BenchResult, mode: &Mode) {
@@ -688,10 +1017,10 @@ fn print_ratio(lean: &BenchResult, rust: &BenchResult, mode: &Mode) {
     } else {
         "N/A".to_string()
     };
-    let time_ratio = if rust.median_wall_ms() > 0 {
+    let time_ratio = if rust.wall_stats().median_wall_ms > 0 {
         format!(
             "{:.1}x",
-            lean.median_wall_ms() as f64 / rust.median_wall_ms() as f64
+            lean.wall_stats().median_wall_ms as f64 / rust.wall_stats().median_wall_ms as f64
         )
     } else {
         "N/A".to_string()
@@ -699,7 +1028,7 @@ fn print_ratio(lean: &BenchResult, rust: &BenchResult, mode: &Mode) {
 
     match mode {
         Mode::Execute => {
-            println!(
+            table!(
                 "{:<14} {:>7} {:>14} {:>10} {:>10}",
                 "Ratio", lean.input, cycle_ratio, seg_ratio, time_ratio,
             );
@@ -713,7 +1042,7 @@ fn print_ratio(lean: &BenchResult, rust: &BenchResult, mode: &Mode) {
                 (Some(l), Some(r)) if r > 0 => format!("{:.1}x", l as f64 / r as f64),
                 _ => "N/A".to_string(),
             };
-            println!(
+            table!(
                 "{:<14} {:>7} {:>14} {:>14} {:>14} {:>10} {:>10}",
                 "Ratio", lean.input, cycle_ratio, total_ratio, paging_ratio, seg_ratio, time_ratio,
             );
@@ -735,7 +1064,7 @@ fn print_eth2_ratio(lean: &Eth2BenchResult, rust: &Eth2BenchResult, mode: &Mode)
 
     match mode {
         Mode::Execute => {
-            println!(
+            table!(
                 "{:<14} {:>7} {:>14} {:>10}",
                 "  Ratio(L/R)", lean.num_validators, cycle_ratio, seg_ratio,
             );
@@ -745,7 +1074,7 @@ fn print_eth2_ratio(lean: &Eth2BenchResult, rust: &Eth2BenchResult, mode: &Mode)
                 (Some(l), Some(r)) if r > 0 => format!("{:.1}x", l as f64 / r as f64),
                 _ => "N/A".to_string(),
             };
-            println!(
+            table!(
                 "{:<14} {:>7} {:>14} {:>14} {:>10}",
                 "  Ratio(L/R)", lean.num_validators, cycle_ratio, total_ratio, seg_ratio,
             );
@@ -753,37 +1082,99 @@ fn print_eth2_ratio(lean: &Eth2BenchResult, rust: &Eth2BenchResult, mode: &Mode)
     }
 }
 
+/// Cycles an init guest variant (pruned, snapshot) saves over the plain init guest
+fn print_init_savings(label: &str, init: &Eth2BenchResult, variant: &Eth2BenchResult) {
+    let change = if init.user_cycles > 0 {
+        format!(
+            "{:+.1}%",
+            (variant.user_cycles as f64 / init.user_cycles as f64 - 1.0) * 100.0
+        )
+    } else {
+        "N/A".to_string()
+    };
+    table!(
+        "  {}: {} -> {} user cycles ({})",
+        label,
+        format_number(init.user_cycles),
+        format_number(variant.user_cycles),
+        change
+    );
+}
+
+fn print_eth2_aggregate_header() {
+    table!(
+        "{:<14} {:>7} {:>14} {:>14} {:>10} {:>10} {:>10}",
+        "Guest", "N", "Block Cycles", "Agg Cycles", "Segments", "Blocks", "Aggregate"
+    );
+    table!("{}", "-".repeat(85));
+}
+
+fn print_eth2_aggregate_result(result: &Eth2AggregateResult) {
+    let status = match &result.outcome {
+        Ok(j) => format!(
+            "{} blocks in {} receipts, up to slot {}",
+            j.blocks, j.receipts, j.slot
+        ),
+        Err(e) => format!("ERR:{}", e),
+    };
+    table!(
+        "{:<14} {:>7} {:>14} {:>14} {:>10} {:>10} {:>10}  [{}]",
+        result.guest_name,
+        result.num_validators,
+        format_number(result.block_user_cycles),
+        format_number(result.aggregate_user_cycles),
+        result.segments,
+        format_time(result.blocks_wall_ms),
+        format_time(result.aggregate_wall_ms),
+        status,
+    );
+}
+
 // ── Suite runners ───────────────────────────────
 
-fn run_sum_benchmark(cli: &Cli) {
+fn run_sum_benchmark(cli: &Cli) -> Vec<ResultRecord> {
+    let mut records = Vec::new();
     let run_lean = matches!(cli.guest, GuestChoice::Lean | GuestChoice::Both);
     let run_rust = matches!(cli.guest, GuestChoice::Rust | GuestChoice::Both);
 
     if run_lean {
-        println!(
+        table!(
             "Lean ELF size: {} bytes",
             format_number(METHOD_ELF.len() as u64)
         );
     }
     if run_rust {
-        println!(
+        table!(
             "Rust ELF size: {} bytes",
             format_number(GUEST_RUST_ELF.len() as u64)
         );
     }
     if run_lean && run_rust {
         let ratio = METHOD_ELF.len() as f64 / GUEST_RUST_ELF.len() as f64;
-        println!("ELF size ratio (Lean/Rust): {:.1}x", ratio);
+        table!("ELF size ratio (Lean/Rust): {:.1}x", ratio);
+    }
+    table!();
+
+    if let Some(dir) = &cli.profile {
+        let guests = [(run_lean, Guest::SumLean), (run_rust, Guest::SumRust)];
+        run_profile(cli, dir, &guests, |n| n.to_le_bytes().to_vec());
+        return Vec::new();
     }
-    println!();
 
     print_header(&cli.mode);
 
     for &input in &cli.inputs {
         let lean_result = if run_lean {
             Some(match cli.mode {
-                Mode::Execute => bench_execute(METHOD_ELF, input, cli.runs, "Lean"),
-                Mode::Prove => bench_prove(METHOD_ELF, METHOD_ID, input, cli.runs, "Lean"),
+                Mode::Execute => bench_execute(METHOD_ELF, input, cli.runs(), "Lean"),
+                Mode::Prove => bench_prove(
+                    Guest::SumLean,
+                    input,
+                    cli.runs(),
+                    "Lean",
+                    cli.receipt_kind,
+                    cli.save_receipts.as_deref(),
+                ),
             })
         } else {
             None
@@ -791,10 +1182,15 @@ fn run_sum_benchmark(cli: &Cli) {
 
         let rust_result = if run_rust {
             Some(match cli.mode {
-                Mode::Execute => bench_execute(GUEST_RUST_ELF, input, cli.runs, "Rust"),
-                Mode::Prove => {
-                    bench_prove(GUEST_RUST_ELF, GUEST_RUST_ID, input, cli.runs, "Rust")
-                }
+                Mode::Execute => bench_execute(GUEST_RUST_ELF, input, cli.runs(), "Rust"),
+                Mode::Prove => bench_prove(
+                    Guest::SumRust,
+                    input,
+                    cli.runs(),
+                    "Rust",
+                    cli.receipt_kind,
+                    cli.save_receipts.as_deref(),
+                ),
             })
         } else {
             None
@@ -810,21 +1206,24 @@ fn run_sum_benchmark(cli: &Cli) {
 
         if let Some(ref r) = lean_result {
             print_result(r, &cli.mode);
+            records.push(ResultRecord::sum(r, Guest::SumLean));
         }
         if let Some(ref r) = rust_result {
             print_result(r, &cli.mode);
+            records.push(ResultRecord::sum(r, Guest::SumRust));
         }
         if let (Some(ref lean), Some(ref rust)) = (&lean_result, &rust_result) {
             print_ratio(lean, rust, &cli.mode);
         }
 
         if cli.inputs.last() != Some(&input) {
-            println!();
+            table!();
         }
     }
+    records
 }
 
-fn run_eth2_benchmark(cli: &Cli) {
+fn run_eth2_benchmark(cli: &Cli) -> Vec<ResultRecord> {
     let run_noinit = matches!(
         cli.guest,
         GuestChoice::LeanNoinit | GuestChoice::All | GuestChoice::Both | GuestChoice::Lean
@@ -833,41 +1232,120 @@ fn run_eth2_benchmark(cli: &Cli) {
         cli.guest,
         GuestChoice::LeanInit | GuestChoice::All | GuestChoice::Both
     );
+    let run_pruned = matches!(cli.guest, GuestChoice::LeanPruned | GuestChoice::All);
+    let run_snapshot = matches!(cli.guest, GuestChoice::LeanSnapshot | GuestChoice::All);
     let run_rust = matches!(
         cli.guest,
         GuestChoice::Rust | GuestChoice::All | GuestChoice::Both
     );
 
-    println!("=== ETH2 State Transition Benchmark ===");
-    println!("State: slot 100 -> 101 (no epoch boundary)");
-    println!();
+    if cli.aggregate && !matches!(cli.mode, Mode::Prove) {
+        eprintln!("--aggregate needs --mode prove");
+        std::process::exit(2);
+    }
+    if cli.profile.is_some() && !matches!(cli.mode, Mode::Execute) {
+        eprintln!("--profile needs --mode execute");
+        std::process::exit(2);
+    }
+    let run_lean = run_noinit || run_init || run_pruned || run_snapshot;
+    if cli.validate_state_root && run_lean {
+        eprintln!("--validate-state-root needs --guest rust: the Lean guests stub hash_tree_root");
+        std::process::exit(2);
+    }
+    if !cli.verify_signatures.is_empty() && run_lean {
+        eprintln!("--verify-signatures needs --guest rust: the Lean guests stub BLS");
+        std::process::exit(2);
+    }
+    // The aggregation guest rejects the Lean guests' zero roots, which would chain to anything
+    if cli.aggregate && run_lean {
+        eprintln!("--aggregate needs --guest rust: the Lean guests commit zero state roots");
+        std::process::exit(2);
+    }
+
+    table!("=== ETH2 State Transition Benchmark ===");
+    table!("Scenario: {}", cli.scenario.description());
+    if cli.blocks > 1 {
+        table!("Blocks: {} per input, at consecutive slots", cli.blocks);
+    }
+    if cli.validate_state_root {
+        table!("State root validation: on (block carries hash_tree_root(post_state))");
+    }
+    let input_options = eth2_testdata::InputOptions {
+        validate_state_root: cli.validate_state_root,
+        signature_checks: cli.verify_signatures.iter().fold(0, |m, c| m | c.mask()),
+        emit_post_state: cli.emit_post_state,
+    };
+    if input_options.signature_checks != 0 {
+        table!(
+            "Signature checks (Rust guest): mask {:#05x}",
+            input_options.signature_checks
+        );
+    }
+    table!();
 
     // Print ELF sizes
     if run_noinit {
-        println!(
+        table!(
             "Lean (no-init) ELF size: {} bytes",
             format_number(GUEST_ETH2_NOINIT_ELF.len() as u64)
         );
     }
     if run_init {
-        println!(
+        table!(
             "Lean (init)    ELF size: {} bytes",
             format_number(GUEST_ETH2_INIT_ELF.len() as u64)
         );
     }
+    if run_pruned {
+        table!(
+            "Lean (pruned)  ELF size: {} bytes",
+            format_number(GUEST_ETH2_INIT_PRUNED_ELF.len() as u64)
+        );
+    }
+    if run_snapshot {
+        table!(
+            "Lean (snapshot) ELF size: {} bytes{}",
+            format_number(GUEST_ETH2_SNAPSHOT_ELF.len() as u64),
+            if GUEST_ETH2_SNAPSHOT_APPLIED { "" } else { " (snapshot not applied: plain init guest)" }
+        );
+    }
     if run_rust {
-        println!(
+        table!(
             "Rust           ELF size: {} bytes",
             format_number(GUEST_RUST_ETH2_ELF.len() as u64)
         );
     }
-    println!();
+    table!();
+
+    if cli.aggregate {
+        run_eth2_aggregate(cli, input_options);
+        return Vec::new();
+    }
+    if let Some(dir) = &cli.profile {
+        let guests = [
+            (run_noinit, Guest::Eth2LeanNoinit),
+            (run_init, Guest::Eth2LeanInit),
+            (run_pruned, Guest::Eth2LeanPruned),
+            (run_snapshot, Guest::Eth2LeanSnapshot),
+            (run_rust, Guest::Eth2Rust),
+        ];
+        run_profile(cli, dir, &guests, |n| {
+            eth2_testdata::build_test_input(n as usize, cli.scenario, cli.blocks as usize, input_options)
+        });
+        return Vec::new();
+    }
 
     print_header(&cli.mode);
+    let mut records = Vec::new();
 
     for &num_val in &cli.inputs {
-        let test_input = eth2_testdata::build_test_input(num_val as usize);
-        println!(
+        let test_input = eth2_testdata::build_test_input(
+            num_val as usize,
+            cli.scenario,
+            cli.blocks as usize,
+            input_options,
+        );
+        table!(
             "  [input: {} validators, {} bytes serialized]",
             num_val,
             format_number(test_input.len() as u64)
@@ -879,16 +1357,17 @@ fn run_eth2_benchmark(cli: &Cli) {
                     GUEST_ETH2_NOINIT_ELF,
                     &test_input,
                     num_val,
-                    cli.runs,
+                    cli.runs(),
                     "Lean(no-init)",
                 ),
                 Mode::Prove => bench_eth2_prove(
-                    GUEST_ETH2_NOINIT_ELF,
-                    GUEST_ETH2_NOINIT_ID,
+                    Guest::Eth2LeanNoinit,
                     &test_input,
                     num_val,
-                    cli.runs,
+                    cli.runs(),
                     "Lean(no-init)",
+                    cli.receipt_kind,
+                    cli.save_receipts.as_deref(),
                 ),
             })
         } else {
@@ -901,16 +1380,63 @@ fn run_eth2_benchmark(cli: &Cli) {
                     GUEST_ETH2_INIT_ELF,
                     &test_input,
                     num_val,
-                    cli.runs,
+                    cli.runs(),
                     "Lean(init)",
                 ),
                 Mode::Prove => bench_eth2_prove(
-                    GUEST_ETH2_INIT_ELF,
-                    GUEST_ETH2_INIT_ID,
+                    Guest::Eth2LeanInit,
                     &test_input,
                     num_val,
-                    cli.runs,
+                    cli.runs(),
                     "Lean(init)",
+                    cli.receipt_kind,
+                    cli.save_receipts.as_deref(),
+                ),
+            })
+        } else {
+            None
+        };
+
+        let pruned_result = if run_pruned {
+            Some(match cli.mode {
+                Mode::Execute => bench_eth2_execute(
+                    GUEST_ETH2_INIT_PRUNED_ELF,
+                    &test_input,
+                    num_val,
+                    cli.runs(),
+                    "Lean(pruned)",
+                ),
+                Mode::Prove => bench_eth2_prove(
+                    Guest::Eth2LeanPruned,
+                    &test_input,
+                    num_val,
+                    cli.runs(),
+                    "Lean(pruned)",
+                    cli.receipt_kind,
+                    cli.save_receipts.as_deref(),
+                ),
+            })
+        } else {
+            None
+        };
+
+        let snapshot_result = if run_snapshot {
+            Some(match cli.mode {
+                Mode::Execute => bench_eth2_execute(
+                    GUEST_ETH2_SNAPSHOT_ELF,
+                    &test_input,
+                    num_val,
+                    cli.runs(),
+                    "Lean(snapshot)",
+                ),
+                Mode::Prove => bench_eth2_prove(
+                    Guest::Eth2LeanSnapshot,
+                    &test_input,
+                    num_val,
+                    cli.runs(),
+                    "Lean(snapshot)",
+                    cli.receipt_kind,
+                    cli.save_receipts.as_deref(),
                 ),
             })
         } else {
@@ -923,16 +1449,17 @@ fn run_eth2_benchmark(cli: &Cli) {
                     GUEST_RUST_ETH2_ELF,
                     &test_input,
                     num_val,
-                    cli.runs,
+                    cli.runs(),
                     "Rust",
                 ),
                 Mode::Prove => bench_eth2_prove(
-                    GUEST_RUST_ETH2_ELF,
-                    GUEST_RUST_ETH2_ID,
+                    Guest::Eth2Rust,
                     &test_input,
                     num_val,
-                    cli.runs,
+                    cli.runs(),
                     "Rust",
+                    cli.receipt_kind,
+                    cli.save_receipts.as_deref(),
                 ),
             })
         } else {
@@ -942,51 +1469,73 @@ fn run_eth2_benchmark(cli: &Cli) {
         // Print results
         if let Some(ref r) = noinit_result {
             print_eth2_result(r, &cli.mode);
+            records.push(ResultRecord::eth2(r, Guest::Eth2LeanNoinit));
         }
         if let Some(ref r) = init_result {
             print_eth2_result(r, &cli.mode);
+            records.push(ResultRecord::eth2(r, Guest::Eth2LeanInit));
+        }
+        if let Some(ref r) = pruned_result {
+            print_eth2_result(r, &cli.mode);
+            records.push(ResultRecord::eth2(r, Guest::Eth2LeanPruned));
+        }
+        if let Some(ref r) = snapshot_result {
+            print_eth2_result(r, &cli.mode);
+            records.push(ResultRecord::eth2(r, Guest::Eth2LeanSnapshot));
         }
         if let Some(ref r) = rust_result {
             print_eth2_result(r, &cli.mode);
+            records.push(ResultRecord::eth2(r, Guest::Eth2Rust));
         }
 
         // Compare outputs between guests
-        let all_results: Vec<&Eth2BenchResult> = [&noinit_result, &init_result, &rust_result]
-            .iter()
-            .filter_map(|r| r.as_ref())
-            .collect();
+        let all_results: Vec<&Eth2BenchResult> =
+            [&noinit_result, &init_result, &pruned_result, &snapshot_result, &rust_result]
+                .iter()
+                .filter_map(|r| r.as_ref())
+                .collect();
 
         if all_results.len() >= 2 {
-            let first = &all_results[0].output_bytes;
+            let first = all_results[0];
             for r in &all_results[1..] {
-                if r.output_bytes != *first {
-                    println!(
-                        "  WARNING: output mismatch between {} and {}!",
-                        all_results[0].guest_name, r.guest_name
-                    );
-                    println!(
-                        "    {} -> {} ({} bytes)",
-                        all_results[0].guest_name,
-                        all_results[0].error_description(),
-                        all_results[0].output_bytes.len()
-                    );
-                    println!(
-                        "    {} -> {} ({} bytes)",
-                        r.guest_name,
-                        r.error_description(),
-                        r.output_bytes.len()
-                    );
-                } else {
-                    println!(
-                        "  OK: {} == {} ({} bytes)",
-                        all_results[0].guest_name,
-                        r.guest_name,
-                        first.len()
-                    );
+                match compare_outputs(first, r) {
+                    Parity::Mismatch(field) => {
+                        table!(
+                            "  WARNING: {} mismatch between {} and {}!",
+                            field, first.guest_name, r.guest_name
+                        );
+                        table!("    {} -> {}", first.guest_name, first.error_description());
+                        table!("    {} -> {}", r.guest_name, r.error_description());
+                    }
+                    // Status alone says nothing about the state both guests computed
+                    Parity::Match(compared) if compared.is_empty() => {
+                        table!(
+                            "  NOT COMPARED: {} vs {}: status, slot and blocks_applied agree, but no state root or post-state was comparable (rerun with --emit-post-state)",
+                            first.guest_name, r.guest_name
+                        );
+                    }
+                    Parity::Match(compared) => {
+                        table!(
+                            "  OK: {} == {} ({})",
+                            first.guest_name,
+                            r.guest_name,
+                            compared.join(", ")
+                        );
+                    }
                 }
             }
         }
 
+        if let (Some(ref init), Some(ref pruned)) = (&init_result, &pruned_result) {
+            print_init_savings("Init pruning", init, pruned);
+        }
+        // Without a snapshot the variant is the init guest, and its savings would be noise
+        if let (Some(ref init), Some(ref snapshot), true) =
+            (&init_result, &snapshot_result, GUEST_ETH2_SNAPSHOT_APPLIED)
+        {
+            print_init_savings("Heap snapshot", init, snapshot);
+        }
+
         // Print Lean/Rust ratios if we have both
         if let (Some(ref lean), Some(ref rust)) = (&init_result, &rust_result) {
             print_eth2_ratio(lean, rust, &cli.mode);
@@ -995,11 +1544,636 @@ fn run_eth2_benchmark(cli: &Cli) {
         }
 
         if cli.inputs.last() != Some(&num_val) {
-            println!();
+            table!();
+        }
+    }
+    records
+}
+
+/// One proof per block for each selected guest, composed into a single receipt.
+/// The Lean guests' roots are still placeholders, so only the Rust guest's chain
+/// check is meaningful.
+fn run_eth2_aggregate(cli: &Cli, input_options: eth2_testdata::InputOptions) {
+    table!(
+        "Aggregation: {} per-block receipt(s) composed by the aggregation guest",
+        cli.blocks
+    );
+    table!();
+    print_eth2_aggregate_header();
+
+    for &num_val in &cli.inputs {
+        let inputs = eth2_testdata::build_block_inputs(
+            num_val as usize,
+            cli.scenario,
+            cli.blocks as usize,
+            input_options,
+        );
+        let result =
+            bench_eth2_aggregate(GUEST_RUST_ETH2_ELF, GUEST_RUST_ETH2_ID, &inputs, num_val, "Rust");
+        print_eth2_aggregate_result(&result);
+        if cli.inputs.last() != Some(&num_val) {
+            table!();
         }
     }
 }
 
+// ── Cycle profiles ──────────────────────────────
+
+/// Execute `guest` once with the profiler writing to `path`; `input` is the guest input as
+/// `host execute` reads it (u32 LE for the sum guests)
+fn profile_guest(guest: Guest, input: &[u8], path: &Path) -> anyhow::Result<(u64, CycleProfile)> {
+    let mut builder = ExecutorEnv::builder();
+    if guest.is_eth2() {
+        builder.write(&input.to_vec())?;
+        builder.write_fd(POST_STATE_FD, io::sink());
+    } else {
+        builder.write(&u32::from_le_bytes(input.try_into()?))?;
+    }
+    let env = builder.enable_profiler(path).build()?;
+    let session = default_executor().execute(env, guest.elf())?;
+    let pprof = fs::read(path)?;
+    Ok((session.cycles(), CycleProfile::from_pprof(&pprof, guest.elf())?))
+}
+
+fn print_profile(guest: Guest, profile: &CycleProfile, top: usize) {
+    let share = |cycles: u64| 100.0 * cycles as f64 / profile.total_cycles.max(1) as f64;
+    table!(
+        "{:>4} {:<60} {:<13} {:>14} {:>7}",
+        "#", guest.name(), "Category", "Cycles", "Share"
+    );
+    for (rank, f) in profile.functions.iter().take(top).enumerate() {
+        let mut name = f.name.clone();
+        if name.chars().count() > 60 {
+            name = name.chars().take(57).collect::<String>() + "...";
+        }
+        table!(
+            "{:>4} {:<60} {:<13} {:>14} {:>6.1}%",
+            rank + 1,
+            name,
+            f.category.name(),
+            format_number(f.cycles),
+            share(f.cycles)
+        );
+    }
+    if profile.functions.len() > top {
+        table!("{:>4} ({} more functions)", "", profile.functions.len() - top);
+    }
+}
+
+/// Cycles per category, one column per guest
+fn print_profile_categories(profiles: &[(Guest, CycleProfile)]) {
+    let mut header = format!("{:<13}", "Category");
+    for (guest, _) in profiles {
+        header += &format!(" {:>16} {:>7}", guest.name(), "");
+    }
+    table!("{}", header.trim_end());
+    table!("{}", "-".repeat(13 + 25 * profiles.len()));
+    let by_category: Vec<_> = profiles.iter().map(|(_, p)| p.by_category()).collect();
+    for (i, category) in Category::ALL.iter().enumerate() {
+        let mut row = format!("{:<13}", category.name());
+        for ((_, profile), categories) in profiles.iter().zip(&by_category) {
+            let cycles = categories[i].1;
+            let share = 100.0 * cycles as f64 / profile.total_cycles.max(1) as f64;
+            row += &format!(" {:>16} {:>6.1}%", format_number(cycles), share);
+        }
+        table!("{}", row);
+    }
+    let mut total = format!("{:<13}", "Total");
+    for (_, profile) in profiles {
+        total += &format!(" {:>16} {:>7}", format_number(profile.total_cycles), "");
+    }
+    table!("{}", total.trim_end());
+}
+
+/// --profile: one profiled execution per selected guest and input
+fn run_profile(cli: &Cli, dir: &Path, guests: &[(bool, Guest)], input_for: impl Fn(u32) -> Vec<u8>) {
+    if let Err(e) = fs::create_dir_all(dir) {
+        eprintln!("error: creating {}: {}", dir.display(), e);
+        process::exit(1);
+    }
+    for &n in &cli.inputs {
+        let input = input_for(n);
+        let mut profiles = Vec::new();
+        for &(selected, guest) in guests {
+            if !selected {
+                continue;
+            }
+            let path = dir.join(format!("{}-{}.pb", guest.name(), n));
+            match profile_guest(guest, &input, &path) {
+                Ok((user_cycles, profile)) => {
+                    table!(
+                        "=== {}, N={}: {} user cycles, {} profiled, {} ===",
+                        guest.name(),
+                        n,
+                        format_number(user_cycles),
+                        format_number(profile.total_cycles),
+                        path.display()
+                    );
+                    print_profile(guest, &profile, cli.profile_top);
+                    table!();
+                    profiles.push((guest, profile));
+                }
+                Err(e) => eprintln!("  {} PROFILE FAILED: {:#}", guest.name(), e),
+            }
+        }
+        if !profiles.is_empty() {
+            table!("=== Cycles by category, N={} ===", n);
+            print_profile_categories(&profiles);
+            if cli.inputs.last() != Some(&n) {
+                table!();
+            }
+        }
+    }
+}
+
+// ── Machine-readable output ─────────────────────
+
+/// Run-wide context recorded with --format json/csv
+#[derive(Serialize, Deserialize)]
+struct RunMetadata {
+    /// `git rev-parse HEAD` of the working directory, if it is a git checkout
+    git_revision: Option<String>,
+    /// Unix time the results were written
+    timestamp: u64,
+    suite: String,
+    mode: String,
+    /// RISC0_DEV_MODE: proofs are fake, so prove-mode timings are meaningless
+    dev_mode: bool,
+    risc0_version: String,
+    host_cpu: String,
+    host_threads: usize,
+    host_os: String,
+    host_arch: String,
+    runs: usize,
+    #[serde(default)]
+    warmup: usize,
+    receipt_kind: String,
+    /// Eth2 input shape; the input options are recorded even for the sum suite
+    scenario: String,
+    blocks: u32,
+    validate_state_root: bool,
+    signature_checks: u32,
+}
+
+/// One guest run. Sum and eth2 results share the columns; fields that do not apply are null.
+#[derive(Serialize, Deserialize)]
+struct ResultRecord {
+    guest: String,
+    image_id: String,
+    elf_bytes: usize,
+    /// N for sum, number of validators for eth2
+    input: u32,
+    user_cycles: u64,
+    total_cycles: Option<u64>,
+    paging_cycles: Option<u64>,
+    segments: usize,
+    compress_ms: Option<u128>,
+    receipt_bytes: Option<u64>,
+    #[serde(flatten)]
+    wall: WallStats,
+    wall_times_ms: Vec<u128>,
+    #[serde(default)]
+    run_issues: Vec<String>,
+    /// Sum guests: the computed sum
+    output: Option<u32>,
+    /// Eth2 guests: "OK", the error, or "CRASHED"
+    status: Option<String>,
+    slot: Option<u64>,
+    blocks_applied: Option<u32>,
+    pre_state_root: Option<String>,
+    block_root: Option<String>,
+    post_state_root: Option<String>,
+    journal_bytes: Option<usize>,
+    post_state_bytes: Option<usize>,
+}
+
+impl ResultRecord {
+    fn new(guest: Guest, input: u32) -> Self {
+        ResultRecord {
+            guest: guest.name().to_string(),
+            image_id: guest.image_id().to_string(),
+            elf_bytes: guest.elf().len(),
+            input,
+            user_cycles: 0,
+            total_cycles: None,
+            paging_cycles: None,
+            segments: 0,
+            compress_ms: None,
+            receipt_bytes: None,
+            wall: WallStats::default(),
+            wall_times_ms: Vec::new(),
+            run_issues: Vec::new(),
+            output: None,
+            status: None,
+            slot: None,
+            blocks_applied: None,
+            pre_state_root: None,
+            block_root: None,
+            post_state_root: None,
+            journal_bytes: None,
+            post_state_bytes: None,
+        }
+    }
+
+    fn sum(result: &BenchResult, guest: Guest) -> Self {
+        ResultRecord {
+            user_cycles: result.user_cycles,
+            total_cycles: result.total_cycles,
+            paging_cycles: result.paging_cycles,
+            segments: result.segments,
+            compress_ms: result.compress_ms,
+            receipt_bytes: result.receipt_bytes,
+            wall: result.wall_stats(),
+            wall_times_ms: result.wall_times_ms.clone(),
+            run_issues: result.run_issues.clone(),
+            output: Some(result.output),
+            ..ResultRecord::new(guest, result.input)
+        }
+    }
+
+    fn eth2(result: &Eth2BenchResult, guest: Guest) -> Self {
+        let journal = result.journal.as_ref();
+        let root = |r: fn(&Eth2Journal) -> &Root| journal.map(|j| hex::encode(r(j)));
+        ResultRecord {
+            user_cycles: result.user_cycles,
+            total_cycles: result.total_cycles,
+            paging_cycles: result.paging_cycles,
+            segments: result.segments,
+            compress_ms: result.compress_ms,
+            receipt_bytes: result.receipt_bytes,
+            wall: result.wall_stats(),
+            wall_times_ms: result.wall_times_ms.clone(),
+            run_issues: result.run_issues.clone(),
+            status: Some(result.error_description()),
+            slot: journal.map(|j| j.slot),
+            blocks_applied: journal.map(|j| j.blocks_applied),
+            pre_state_root: root(|j| &j.pre_state_root),
+            block_root: root(|j| &j.block_root),
+            post_state_root: root(|j| &j.post_state_root),
+            journal_bytes: Some(result.journal_bytes),
+            post_state_bytes: Some(result.post_state.len()),
+            ..ResultRecord::new(guest, result.num_validators)
+        }
+    }
+
+    const CSV_HEADER: &'static str = "guest,image_id,elf_bytes,input,user_cycles,total_cycles,\
+        paging_cycles,segments,compress_ms,receipt_bytes,mean_wall_ms,stddev_wall_ms,min_wall_ms,\
+        median_wall_ms,p90_wall_ms,p99_wall_ms,max_wall_ms,wall_times_ms,run_issues,output,status,\
+        slot,blocks_applied,pre_state_root,block_root,post_state_root,journal_bytes,\
+        post_state_bytes";
+
+    fn csv_fields(&self) -> Vec<String> {
+        fn opt<T: ToString>(v: &Option<T>) -> String {
+            v.as_ref().map_or(String::new(), T::to_string)
+        }
+        let wall_times: Vec<String> = self.wall_times_ms.iter().map(u128::to_string).collect();
+        vec![
+            self.guest.clone(),
+            self.image_id.clone(),
+            self.elf_bytes.to_string(),
+            self.input.to_string(),
+            self.user_cycles.to_string(),
+            opt(&self.total_cycles),
+            opt(&self.paging_cycles),
+            self.segments.to_string(),
+            opt(&self.compress_ms),
+            opt(&self.receipt_bytes),
+            format!("{:.1}", self.wall.mean_wall_ms),
+            format!("{:.1}", self.wall.stddev_wall_ms),
+            self.wall.min_wall_ms.to_string(),
+            self.wall.median_wall_ms.to_string(),
+            self.wall.p90_wall_ms.to_string(),
+            self.wall.p99_wall_ms.to_string(),
+            self.wall.max_wall_ms.to_string(),
+            // One cell each, so the column count does not depend on --runs
+            wall_times.join(";"),
+            self.run_issues.join(";"),
+            opt(&self.output),
+            opt(&self.status),
+            opt(&self.slot),
+            opt(&self.blocks_applied),
+            opt(&self.pre_state_root),
+            opt(&self.block_root),
+            opt(&self.post_state_root),
+            opt(&self.journal_bytes),
+            opt(&self.post_state_bytes),
+        ]
+    }
+}
+
+impl RunMetadata {
+    fn collect(cli: &Cli) -> Self {
+        let name = |v: Option<clap::builder::PossibleValue>| {
+            v.map_or(String::new(), |v| v.get_name().to_string())
+        };
+        RunMetadata {
+            git_revision: git_revision(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            suite: name(cli.suite.to_possible_value()),
+            mode: name(cli.mode.to_possible_value()),
+            dev_mode: ProverOpts::default().dev_mode(),
+            risc0_version: risc0_zkvm::VERSION.to_string(),
+            host_cpu: host_cpu(),
+            host_threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            host_os: std::env::consts::OS.to_string(),
+            host_arch: std::env::consts::ARCH.to_string(),
+            runs: cli.runs,
+            warmup: cli.warmup,
+            receipt_kind: name(cli.receipt_kind.to_possible_value()),
+            scenario: name(cli.scenario.to_possible_value()),
+            blocks: cli.blocks,
+            validate_state_root: cli.validate_state_root,
+            signature_checks: cli.verify_signatures.iter().fold(0, |m, c| m | c.mask()),
+        }
+    }
+
+    // Same order as csv_fields
+    const CSV_HEADER: &'static str = "git_revision,timestamp,suite,mode,dev_mode,risc0_version,\
+        host_cpu,host_threads,host_os,host_arch,runs,warmup,receipt_kind,scenario,blocks,\
+        validate_state_root,signature_checks";
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.git_revision.clone().unwrap_or_default(),
+            self.timestamp.to_string(),
+            self.suite.clone(),
+            self.mode.clone(),
+            self.dev_mode.to_string(),
+            self.risc0_version.clone(),
+            self.host_cpu.clone(),
+            self.host_threads.to_string(),
+            self.host_os.clone(),
+            self.host_arch.clone(),
+            self.runs.to_string(),
+            self.warmup.to_string(),
+            self.receipt_kind.clone(),
+            self.scenario.clone(),
+            self.blocks.to_string(),
+            self.validate_state_root.to_string(),
+            self.signature_checks.to_string(),
+        ]
+    }
+}
+
+fn git_revision() -> Option<String> {
+    let out = process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+fn host_cpu() -> String {
+    // Linux
+    if let Ok(cpuinfo) = fs::read_to_string("/proc/cpuinfo") {
+        let model = cpuinfo
+            .lines()
+            .find(|l| l.starts_with("model name"))
+            .and_then(|l| l.split(':').nth(1));
+        if let Some(model) = model {
+            return model.trim().to_string();
+        }
+    }
+    // macOS
+    process::Command::new("sysctl")
+        .args(["-n", "machdep.cpu.brand_string"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Write json/csv results to --output or stdout; a no-op for the table format
+fn write_report(cli: &Cli, records: &[ResultRecord]) -> io::Result<()> {
+    if cli.format == OutputFormat::Table {
+        return Ok(());
+    }
+    let mut out: Box<dyn Write> = match &cli.output {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    let metadata = RunMetadata::collect(cli);
+    match cli.format {
+        OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct Report<'a> {
+                metadata: &'a RunMetadata,
+                results: &'a [ResultRecord],
+            }
+            serde_json::to_writer_pretty(&mut out, &Report { metadata: &metadata, results: records })?;
+            writeln!(out)?;
+        }
+        // One row per result, with the run metadata repeated so rows can be appended
+        // across runs into one sheet
+        OutputFormat::Csv => {
+            writeln!(out, "{},{}", RunMetadata::CSV_HEADER, ResultRecord::CSV_HEADER)?;
+            let run = metadata.csv_fields();
+            for record in records {
+                let row: Vec<String> = run
+                    .iter()
+                    .chain(&record.csv_fields())
+                    .map(|f| csv_escape(f))
+                    .collect();
+                writeln!(out, "{}", row.join(","))?;
+            }
+        }
+        OutputFormat::Table => unreachable!(),
+    }
+    out.flush()
+}
+
+// ── Baseline comparison ─────────────────────────
+
+/// Exit status when --baseline finds a regression; 1 is an error, 2 a usage error
+const EXIT_REGRESSION: i32 = 3;
+
+/// A previous run's `--format json` output
+#[derive(Deserialize)]
+struct Baseline {
+    metadata: RunMetadata,
+    results: Vec<ResultRecord>,
+}
+
+impl Baseline {
+    fn read(path: &Path) -> io::Result<Self> {
+        let file = io::BufReader::new(fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Print every metric that changed against the baseline; returns whether any regressed
+    fn compare(&self, cli: &Cli, records: &[ResultRecord]) -> bool {
+        let current = RunMetadata::collect(cli);
+        let base = &self.metadata;
+        table!();
+        table!(
+            "=== Baseline comparison ({}) ===",
+            base.git_revision.as_deref().unwrap_or("unknown revision")
+        );
+        // Cycles and statuses depend on the inputs, wall times also on the mode and the machine
+        let compare_cycles = (
+            &base.suite,
+            &base.scenario,
+            base.blocks,
+            base.validate_state_root,
+            base.signature_checks,
+        ) == (
+            &current.suite,
+            &current.scenario,
+            current.blocks,
+            current.validate_state_root,
+            current.signature_checks,
+        );
+        if !compare_cycles {
+            table!(
+                "note: the baseline was run with different input options; \
+                 cycles and statuses are not compared"
+            );
+        }
+        let compare_time = base.mode == current.mode && base.dev_mode == current.dev_mode;
+        if !compare_time {
+            table!("note: the baseline was run in another mode; wall times are not compared");
+        } else if base.host_cpu != current.host_cpu {
+            table!(
+                "warning: the baseline was run on {}; wall times may not be comparable",
+                base.host_cpu
+            );
+        }
+
+        table!(
+            "{:<14} {:>7} {:<14} {:>14} {:>14} {:>9}",
+            "Guest",
+            "N",
+            "Metric",
+            "Baseline",
+            "Current",
+            "Change"
+        );
+        table!("{}", "-".repeat(77));
+        let mut regressed = false;
+        let mut changed = false;
+        for record in records {
+            let Some(old) = self
+                .results
+                .iter()
+                .find(|old| old.guest == record.guest && old.input == record.input)
+            else {
+                changed = true;
+                table!("{:<14} {:>7} (not in baseline)", record.guest, record.input);
+                continue;
+            };
+
+            let mut metrics = Vec::new();
+            if compare_cycles {
+                metrics.push((
+                    "user cycles",
+                    old.user_cycles as f64,
+                    record.user_cycles as f64,
+                    cli.cycle_threshold,
+                ));
+                metrics.push((
+                    "segments",
+                    old.segments as f64,
+                    record.segments as f64,
+                    cli.cycle_threshold,
+                ));
+                if let (Some(old_total), Some(total)) = (old.total_cycles, record.total_cycles) {
+                    metrics.push((
+                        "total cycles",
+                        old_total as f64,
+                        total as f64,
+                        cli.cycle_threshold,
+                    ));
+                }
+            }
+            // A run that failed outright has no wall times to compare
+            if compare_time && !old.wall_times_ms.is_empty() && !record.wall_times_ms.is_empty() {
+                metrics.push((
+                    "wall ms",
+                    old.wall.median_wall_ms as f64,
+                    record.wall.median_wall_ms as f64,
+                    cli.time_threshold,
+                ));
+            }
+            for (metric, old_value, value, threshold) in metrics {
+                if value == old_value {
+                    continue;
+                }
+                changed = true;
+                let change = if old_value > 0.0 {
+                    (value - old_value) / old_value * 100.0
+                } else {
+                    f64::INFINITY
+                };
+                let regression = change > threshold;
+                regressed |= regression;
+                table!(
+                    "{:<14} {:>7} {:<14} {:>14} {:>14} {:>+8.1}%{}",
+                    record.guest,
+                    record.input,
+                    metric,
+                    old_value,
+                    value,
+                    change,
+                    if regression { "  REGRESSION" } else { "" }
+                );
+            }
+
+            // An eth2 transition that used to succeed and now fails is a regression whatever the
+            // cycles
+            if compare_cycles
+                && old.status.as_deref() == Some("OK")
+                && record.status.as_deref() != Some("OK")
+            {
+                changed = true;
+                regressed = true;
+                table!(
+                    "{:<14} {:>7} status: OK -> {}  REGRESSION",
+                    record.guest,
+                    record.input,
+                    record.status.as_deref().unwrap_or("missing")
+                );
+            }
+            // Failed warmups and failed or nondeterministic repeat runs are regressions too
+            if old.run_issues.is_empty() && !record.run_issues.is_empty() {
+                changed = true;
+                regressed = true;
+                table!(
+                    "{:<14} {:>7} {} warmup or repeat run(s) failed or diverged  REGRESSION",
+                    record.guest,
+                    record.input,
+                    record.run_issues.len()
+                );
+            }
+        }
+        for old in &self.results {
+            if !records
+                .iter()
+                .any(|record| record.guest == old.guest && record.input == old.input)
+            {
+                changed = true;
+                table!("{:<14} {:>7} (not in current run)", old.guest, old.input);
+            }
+        }
+        if !changed {
+            table!("No changes against the baseline");
+        }
+        regressed
+    }
+}
+
 // ── Main ────────────────────────────────────────
 
 fn main() {
@@ -1008,9 +2182,36 @@ fn main() {
         .init();
 
     let cli = Cli::parse();
+    // Their results are tables only: a report would be empty and every baseline entry missing
+    if (cli.aggregate || cli.profile.is_some())
+        && (cli.format != OutputFormat::Table || cli.baseline.is_some())
+    {
+        eprintln!("--aggregate and --profile print tables only: drop --format json|csv and --baseline");
+        process::exit(2);
+    }
+    // Read the baseline before benchmarking, so a bad path fails fast
+    let baseline = cli.baseline.as_deref().map(|path| {
+        Baseline::read(path).unwrap_or_else(|e| {
+            eprintln!("error: reading baseline {}: {}", path.display(), e);
+            process::exit(1);
+        })
+    });
+    TABLE_ON_STDERR.store(
+        cli.format != OutputFormat::Table && cli.output.is_none(),
+        Ordering::Relaxed,
+    );
 
-    match cli.suite {
+    let records = match cli.suite {
         Suite::Sum => run_sum_benchmark(&cli),
         Suite::Eth2 => run_eth2_benchmark(&cli),
+    };
+    if let Err(e) = write_report(&cli, &records) {
+        eprintln!("error: writing results: {}", e);
+        process::exit(1);
+    }
+    if let Some(baseline) = baseline {
+        if baseline.compare(&cli, &records) {
+            process::exit(EXIT_REGRESSION);
+        }
     }
 }
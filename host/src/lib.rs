@@ -0,0 +1,146 @@
+//! Pieces shared by the `host` and `benchmark` binaries.
+
+pub mod eth2_testdata;
+pub mod profile;
+pub mod receipt_file;
+
+// Rust eth2 guest STF, compiled natively so the test builder can fill in block.state_root.
+// The builder thus agrees with the guest by construction; eth2_testdata::tests checks the
+// roots it produces against independently derived known answers.
+#[allow(dead_code)]
+#[path = "../../methods/guest-rust-eth2/src/crypto.rs"]
+mod crypto;
+#[allow(dead_code)]
+#[path = "../../methods/guest-rust-eth2/src/helpers.rs"]
+mod helpers;
+#[allow(dead_code)]
+#[path = "../../methods/guest-rust-eth2/src/ssz.rs"]
+mod ssz;
+#[allow(dead_code)]
+#[path = "../../methods/guest-rust-eth2/src/transition.rs"]
+mod transition;
+#[allow(dead_code)]
+#[path = "../../methods/guest-rust-eth2/src/types.rs"]
+mod types;
+
+use clap::ValueEnum;
+use methods::{
+    GUEST_ETH2_INIT_ELF, GUEST_ETH2_INIT_ID, GUEST_ETH2_INIT_PRUNED_ELF, GUEST_ETH2_INIT_PRUNED_ID,
+    GUEST_ETH2_NOINIT_ELF, GUEST_ETH2_NOINIT_ID, GUEST_ETH2_SNAPSHOT_ELF, GUEST_ETH2_SNAPSHOT_ID,
+    GUEST_RUST_ELF, GUEST_RUST_ETH2_ELF, GUEST_RUST_ETH2_ID, GUEST_RUST_ID, METHOD_ELF, METHOD_ID,
+};
+use risc0_zkvm::sha::Digest;
+use risc0_zkvm::{InnerReceipt, Prover, ProverOpts, Receipt};
+use std::time::{Duration, Instant};
+
+/// A provable guest, named as on the `host --guest` command line and in receipt files
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Guest {
+    SumLean,
+    SumRust,
+    Eth2LeanInit,
+    /// The init guest built with only the Init modules it reaches
+    Eth2LeanPruned,
+    /// The init guest starting from a heap snapshot taken after Lean initialization
+    Eth2LeanSnapshot,
+    Eth2LeanNoinit,
+    Eth2Rust,
+}
+
+impl Guest {
+    pub fn elf(self) -> &'static [u8] {
+        match self {
+            Guest::SumLean => METHOD_ELF,
+            Guest::SumRust => GUEST_RUST_ELF,
+            Guest::Eth2LeanInit => GUEST_ETH2_INIT_ELF,
+            Guest::Eth2LeanPruned => GUEST_ETH2_INIT_PRUNED_ELF,
+            Guest::Eth2LeanSnapshot => GUEST_ETH2_SNAPSHOT_ELF,
+            Guest::Eth2LeanNoinit => GUEST_ETH2_NOINIT_ELF,
+            Guest::Eth2Rust => GUEST_RUST_ETH2_ELF,
+        }
+    }
+
+    pub fn image_id(self) -> Digest {
+        match self {
+            Guest::SumLean => METHOD_ID,
+            Guest::SumRust => GUEST_RUST_ID,
+            Guest::Eth2LeanInit => GUEST_ETH2_INIT_ID,
+            Guest::Eth2LeanPruned => GUEST_ETH2_INIT_PRUNED_ID,
+            Guest::Eth2LeanSnapshot => GUEST_ETH2_SNAPSHOT_ID,
+            Guest::Eth2LeanNoinit => GUEST_ETH2_NOINIT_ID,
+            Guest::Eth2Rust => GUEST_RUST_ETH2_ID,
+        }
+        .into()
+    }
+
+    pub fn is_eth2(self) -> bool {
+        !matches!(self, Guest::SumLean | Guest::SumRust)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Guest::SumLean => "sum-lean",
+            Guest::SumRust => "sum-rust",
+            Guest::Eth2LeanInit => "eth2-lean-init",
+            Guest::Eth2LeanPruned => "eth2-lean-pruned",
+            Guest::Eth2LeanSnapshot => "eth2-lean-snapshot",
+            Guest::Eth2LeanNoinit => "eth2-lean-noinit",
+            Guest::Eth2Rust => "eth2-rust",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Guest::value_variants().iter().copied().find(|g| g.name() == name)
+    }
+
+    pub fn from_image_id(image_id: Digest) -> Option<Self> {
+        Guest::value_variants()
+            .iter()
+            .copied()
+            .find(|g| g.image_id() == image_id)
+    }
+}
+
+/// Receipt kind requested from the prover: composite (one seal per segment, so its size
+/// grows with the segment count) or compressed into a single succinct STARK
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReceiptKind {
+    #[default]
+    Composite,
+    Succinct,
+}
+
+impl ReceiptKind {
+    /// Compress a freshly proven composite receipt into this kind, returning how long it
+    /// took (None for composite, which needs no compression)
+    pub fn compress(
+        self,
+        prover: &dyn Prover,
+        receipt: Receipt,
+    ) -> anyhow::Result<(Receipt, Option<Duration>)> {
+        match self {
+            ReceiptKind::Composite => Ok((receipt, None)),
+            ReceiptKind::Succinct => {
+                let start = Instant::now();
+                let receipt = prover.compress(&ProverOpts::succinct(), &receipt)?;
+                Ok((receipt, Some(start.elapsed())))
+            }
+        }
+    }
+}
+
+/// Name of the receipt's actual kind, for display
+pub fn receipt_kind_name(receipt: &Receipt) -> &'static str {
+    match &receipt.inner {
+        InnerReceipt::Composite(_) => "composite",
+        InnerReceipt::Succinct(_) => "succinct",
+        InnerReceipt::Groth16(_) => "groth16",
+        InnerReceipt::Fake(_) => "fake",
+        _ => "unknown",
+    }
+}
+
+/// Encoded size of the receipt, as written to a receipt file or sent over the wire
+pub fn receipt_size(receipt: &Receipt) -> u64 {
+    bincode::serialized_size(receipt).unwrap_or(0)
+}
@@ -1,28 +1,276 @@
-use methods::METHOD_ELF;
-use risc0_zkvm::{default_prover, ExecutorEnv};
-use std::env;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use eth2_journal::{Eth2Journal, Eth2Status, POST_STATE_FD};
+use host::receipt_file::{self, ReceiptHeader};
+use host::{Guest, ReceiptKind};
+use risc0_zkvm::{default_executor, default_prover, ExecutorEnv, Journal};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+use std::{fs, io};
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+// Exit codes; clap itself exits with 2 on usage errors
+/// I/O, decoding, execution or proving error
+const EXIT_FAILURE: u8 = 1;
+/// The receipt does not verify against the expected image ID or input
+const EXIT_INVALID_RECEIPT: u8 = 3;
+/// The eth2 guest ran but reported a failed state transition
+const EXIT_REJECTED: u8 = 4;
 
-    let input: u32 = if args.len() > 1 {
-        args[1].parse().expect("Please provide a valid number")
+#[derive(Parser)]
+#[command(name = "host", about = "Execute, prove and verify the Lean and Rust guests")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a guest in the executor and print its journal
+    Execute {
+        #[arg(long)]
+        guest: Guest,
+
+        /// Input file: a decimal N for the sum guests, serialized eth2 input for the eth2 guests
+        #[arg(long)]
+        input: PathBuf,
+    },
+
+    /// Prove a guest, print its journal and write the receipt
+    Prove {
+        #[arg(long)]
+        guest: Guest,
+
+        /// Input file: a decimal N for the sum guests, serialized eth2 input for the eth2 guests
+        #[arg(long)]
+        input: PathBuf,
+
+        /// Where to write the receipt file
+        #[arg(long, default_value = "receipt.bin")]
+        receipt: PathBuf,
+
+        /// Kind of receipt to write; succinct compresses the composite receipt after proving
+        #[arg(long, value_enum, default_value_t)]
+        receipt_kind: ReceiptKind,
+    },
+
+    /// Verify a receipt file against the image ID of the guest that should have produced it
+    Verify {
+        receipt: PathBuf,
+
+        /// Expected guest; defaults to the one named in the receipt file
+        #[arg(long)]
+        guest: Option<Guest>,
+
+        /// Also check that the receipt was proven for this input file
+        #[arg(long)]
+        input: Option<PathBuf>,
+    },
+
+    /// Print a receipt file's header and decode its journal
+    Inspect { receipt: PathBuf },
+}
+
+// ── Input and journal ───────────────────────────
+
+/// Guest input from a file: the u32 LE for the sum guests (the file holds it in decimal),
+/// the file's bytes as-is for the eth2 guests. These are the bytes a receipt file hashes.
+fn read_input(guest: Guest, input_path: &Path) -> Result<Vec<u8>> {
+    let input = fs::read(input_path)
+        .with_context(|| format!("reading input {}", input_path.display()))?;
+    if guest.is_eth2() {
+        return Ok(input);
+    }
+    let n: u32 = std::str::from_utf8(&input)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .with_context(|| format!("{} must contain a decimal u32", input_path.display()))?;
+    Ok(n.to_le_bytes().to_vec())
+}
+
+fn build_env(guest: Guest, input: &[u8]) -> Result<ExecutorEnv<'static>> {
+    let mut builder = ExecutorEnv::builder();
+    if guest.is_eth2() {
+        builder.write(&input.to_vec())?;
+        // The optional post-state is private output; this CLI only reports the journal
+        builder.write_fd(POST_STATE_FD, io::sink());
     } else {
-        eprintln!("Error: Please provide a number as an argument");
-        std::process::exit(1);
+        builder.write(&u32::from_le_bytes(input.try_into()?))?;
+    }
+    builder.build()
+}
+
+/// Print the journal and return the exit code it implies
+fn report_journal(guest: Guest, journal: &Journal) -> Result<ExitCode> {
+    if !guest.is_eth2() {
+        let result: u32 = journal.decode().context("decoding sum journal")?;
+        println!("Result: {}", result);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let journal: Eth2Journal = journal.decode().context("decoding eth2 journal")?;
+    println!("Slot:            {}", journal.slot);
+    println!("Blocks applied:  {}", journal.blocks_applied);
+    println!("Pre-state root:  0x{}", hex::encode(journal.pre_state_root));
+    println!("Block root:      0x{}", hex::encode(journal.block_root));
+    println!("Post-state root: 0x{}", hex::encode(journal.post_state_root));
+    match &journal.status {
+        Eth2Status::Ok => {
+            println!("Status:          OK");
+            Ok(ExitCode::SUCCESS)
+        }
+        Eth2Status::Error(e) => {
+            println!("Status:          ERROR: {}", e);
+            Ok(ExitCode::from(EXIT_REJECTED))
+        }
+    }
+}
+
+// ── Commands ────────────────────────────────────
+
+fn execute(guest: Guest, input_path: &Path) -> Result<ExitCode> {
+    let env = build_env(guest, &read_input(guest, input_path)?)?;
+    let session = default_executor()
+        .execute(env, guest.elf())
+        .with_context(|| format!("executing {}", guest.name()))?;
+    println!("Guest:           {}", guest.name());
+    println!("User cycles:     {}", session.cycles());
+    println!("Segments:        {}", session.segments.len());
+    report_journal(guest, &session.journal)
+}
+
+fn prove(
+    guest: Guest,
+    input_path: &Path,
+    receipt_path: &Path,
+    receipt_kind: ReceiptKind,
+) -> Result<ExitCode> {
+    let input = read_input(guest, input_path)?;
+    let env = build_env(guest, &input)?;
+    let prover = default_prover();
+    let start = Instant::now();
+    let prove_info = prover
+        .prove(env, guest.elf())
+        .with_context(|| format!("proving {}", guest.name()))?;
+    let prove_time = start.elapsed();
+    let (receipt, compress_time) = receipt_kind
+        .compress(prover.as_ref(), prove_info.receipt)
+        .context("compressing receipt")?;
+    receipt
+        .verify(guest.image_id())
+        .context("freshly proven receipt does not verify")?;
+    let header = ReceiptHeader::new(guest.name(), guest.image_id(), &input);
+    receipt_file::write(receipt_path, &header, &receipt)?;
+    println!("Guest:           {}", guest.name());
+    println!("User cycles:     {}", prove_info.stats.user_cycles);
+    println!("Total cycles:    {}", prove_info.stats.total_cycles);
+    println!("Segments:        {}", prove_info.stats.segments);
+    println!("Proving time:    {:.1?}", prove_time);
+    if let Some(compress_time) = compress_time {
+        println!("Compression:     {:.1?}", compress_time);
+    }
+    println!(
+        "Receipt:         {} ({}, {} bytes)",
+        receipt_path.display(),
+        host::receipt_kind_name(&receipt),
+        host::receipt_size(&receipt)
+    );
+    report_journal(guest, &receipt.journal)
+}
+
+fn verify(receipt_path: &Path, guest: Option<Guest>, input_path: Option<&Path>) -> Result<ExitCode> {
+    let (header, receipt) = receipt_file::read_unchecked(receipt_path)?;
+    warn_version_mismatch(&header);
+    let invalid = |reason: String| {
+        println!("INVALID: {}: {}", receipt_path.display(), reason);
+        Ok(ExitCode::from(EXIT_INVALID_RECEIPT))
     };
+    if let Err(e) = receipt_file::check_image_id(&header, &receipt) {
+        return invalid(format!("{:#}", e));
+    }
 
-    let env = ExecutorEnv::builder()
-        .write(&input)
-        .unwrap()
-        .build()
-        .unwrap();
+    let guest = match guest {
+        Some(guest) if guest.name() != header.guest => {
+            return invalid(format!("proven by {}, expected {}", header.guest, guest.name()));
+        }
+        Some(guest) => guest,
+        None => match Guest::from_name(&header.guest) {
+            Some(guest) => guest,
+            None => bail!("receipt names unknown guest {:?}; pass --guest", header.guest),
+        },
+    };
+    // The expected image ID comes from this build, never from the file
+    if let Err(e) = receipt.verify(guest.image_id()) {
+        return invalid(format!("does not verify as {}: {}", guest.name(), e));
+    }
+    if let Some(input_path) = input_path {
+        let input = read_input(guest, input_path)?;
+        if receipt_file::input_sha256(&input) != header.input_sha256 {
+            return invalid(format!("was not proven for input {}", input_path.display()));
+        }
+    }
 
-    // Prove the execution of the guest ELF
-    let prove_info = default_prover().prove(env, METHOD_ELF).unwrap();
-    let receipt = prove_info.receipt;
+    println!("OK: {} verifies as {}", receipt_path.display(), guest.name());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn inspect(receipt_path: &Path) -> Result<ExitCode> {
+    let (header, receipt) = receipt_file::read(receipt_path)?;
+    warn_version_mismatch(&header);
+    println!("Guest:           {}", header.guest);
+    println!("Image ID:        {}", header.image_id);
+    println!("risc0 version:   {}", header.risc0_version);
+    println!("Input SHA-256:   {}", hex::encode(header.input_sha256));
+    println!(
+        "Receipt:         {}, {} bytes",
+        host::receipt_kind_name(&receipt),
+        host::receipt_size(&receipt)
+    );
+    println!("Journal:         {} bytes", receipt.journal.bytes.len());
+    // Decode by image ID, so a mislabeled file cannot pick the wrong journal type
+    match Guest::from_image_id(header.image_id) {
+        Some(guest) => report_journal(guest, &receipt.journal),
+        None => {
+            println!("(image ID not built into this host; journal not decoded)");
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn warn_version_mismatch(header: &ReceiptHeader) {
+    if header.risc0_version != risc0_zkvm::VERSION {
+        eprintln!(
+            "warning: receipt was proven with risc0-zkvm {}, this host uses {}",
+            header.risc0_version,
+            risc0_zkvm::VERSION
+        );
+    }
+}
+
+// ── Main ────────────────────────────────────────
 
-    // Read the public result back out of the journal
-    let result: u32 = receipt.journal.decode().unwrap();
-    println!("Result for {input} is {result}");
+fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
+        .init();
+
+    let result = match Cli::parse().command {
+        Command::Execute { guest, input } => execute(guest, &input),
+        Command::Prove {
+            guest,
+            input,
+            receipt,
+            receipt_kind,
+        } => prove(guest, &input, &receipt, receipt_kind),
+        Command::Verify {
+            receipt,
+            guest,
+            input,
+        } => verify(&receipt, guest, input.as_deref()),
+        Command::Inspect { receipt } => inspect(&receipt),
+    };
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {:#}", e);
+        ExitCode::from(EXIT_FAILURE)
+    })
 }
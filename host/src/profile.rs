@@ -0,0 +1,275 @@
+//! Per-function cycle attribution from the zkVM profiler's pprof output.
+//!
+//! The executor writes a pprof `Profile` when the env has the profiler enabled
+//! (`ExecutorEnvBuilder::enable_profiler` or `RISC0_PPROF_OUT`). Each sample is a call stack
+//! with the cycles spent in its innermost function, and every location carries that function's
+//! entry address. Addresses are resolved against the symbol table of the guest's user ELF, so
+//! cycles land on the symbol that actually ran.
+
+use anyhow::{Context, Result};
+use elf::abi::{SHN_UNDEF, STT_FUNC};
+use elf::{endian::LittleEndian, ElfBytes};
+use prost::Message;
+use risc0_binfmt::ProgramBinary;
+use std::collections::HashMap;
+
+// The subset of pprof's profile.proto read here
+#[derive(Clone, PartialEq, prost::Message)]
+struct Profile {
+    #[prost(message, repeated, tag = "2")]
+    sample: Vec<Sample>,
+    #[prost(message, repeated, tag = "4")]
+    location: Vec<Location>,
+    #[prost(message, repeated, tag = "5")]
+    function: Vec<Function>,
+    #[prost(string, repeated, tag = "6")]
+    string_table: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Sample {
+    /// Innermost frame first
+    #[prost(uint64, repeated, tag = "1")]
+    location_id: Vec<u64>,
+    /// Cycles
+    #[prost(int64, repeated, tag = "2")]
+    value: Vec<i64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Location {
+    #[prost(uint64, tag = "1")]
+    id: u64,
+    /// Entry address of the function; 0 for the zkVM's own frames ([PageIn], [PageOut])
+    #[prost(uint64, tag = "3")]
+    address: u64,
+    #[prost(message, repeated, tag = "4")]
+    line: Vec<Line>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Line {
+    #[prost(uint64, tag = "1")]
+    function_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Function {
+    #[prost(uint64, tag = "1")]
+    id: u64,
+    /// Index into string_table
+    #[prost(int64, tag = "2")]
+    name: i64,
+}
+
+/// Where a function's cycles go in the Lean vs Rust comparison
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    /// `lean_*` and `lean::*`: reference counting, allocation and runtime primitives
+    LeanRuntime,
+    /// `initialize_*` and the `_init_*` closures they run: Lean module initialization
+    ModuleInit,
+    /// The guest program itself: compiled Lean (`l_*`) or the Rust guest's own code
+    Guest,
+    /// C library, compiler builtins and Rust core/alloc/std
+    Libc,
+    /// zkVM paging and the risc0 guest runtime (entry, syscalls)
+    ZkVm,
+}
+
+impl Category {
+    pub const ALL: [Category; 5] = [
+        Category::LeanRuntime,
+        Category::ModuleInit,
+        Category::Guest,
+        Category::Libc,
+        Category::ZkVm,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::LeanRuntime => "Lean runtime",
+            Category::ModuleInit => "Module init",
+            Category::Guest => "Guest code",
+            Category::Libc => "libc/std",
+            Category::ZkVm => "zkVM",
+        }
+    }
+
+    /// Classify a function by its symbol name
+    pub fn of(name: &str) -> Self {
+        const LIBC: &[&str] = &[
+            "memcpy", "memmove", "memset", "memcmp", "bcmp", "strlen", "strcmp", "strncmp",
+            "strchr", "malloc", "free", "calloc", "realloc", "aligned_alloc", "posix_memalign",
+            "abort", "exit", "snprintf", "vsnprintf", "qsort",
+        ];
+        if name.starts_with("[Page") {
+            Category::ZkVm
+        } else if name.starts_with("initialize_") || name.starts_with("_init_") {
+            Category::ModuleInit
+        } else if name.starts_with("lean_") || name.starts_with("lean::") {
+            Category::LeanRuntime
+        } else if ["risc0_zkvm", "sys_", "__start", "_start", "zkvm_"]
+            .iter()
+            .any(|p| name.starts_with(p))
+        {
+            Category::ZkVm
+        } else if LIBC.contains(&name)
+            // compiler-rt/libgcc helpers (__mulsi3, __udivdi3, ...)
+            || name.starts_with("__")
+            || ["core::", "alloc::", "std::", "compiler_builtins::", "<core::", "<alloc::", "<std::"]
+                .iter()
+                .any(|p| name.starts_with(p))
+        {
+            Category::Libc
+        } else {
+            Category::Guest
+        }
+    }
+}
+
+pub struct FunctionCycles {
+    pub name: String,
+    pub category: Category,
+    /// Cycles spent in the function itself, not its callees
+    pub cycles: u64,
+}
+
+/// Self cycles per function for one execution
+pub struct CycleProfile {
+    pub total_cycles: u64,
+    /// Most cycles first
+    pub functions: Vec<FunctionCycles>,
+}
+
+impl CycleProfile {
+    /// Attribute the samples of a pprof profile written while executing `elf`, the
+    /// program binary as embedded by the methods crate
+    pub fn from_pprof(pprof: &[u8], elf: &[u8]) -> Result<Self> {
+        let profile = Profile::decode(pprof).context("decoding pprof profile")?;
+        let symbols = Symbols::from_elf(elf)?;
+
+        let string = |i: i64| {
+            let i = usize::try_from(i).unwrap_or(0);
+            profile.string_table.get(i).map_or("", String::as_str)
+        };
+        let function_names: HashMap<u64, &str> =
+            profile.function.iter().map(|f| (f.id, string(f.name))).collect();
+        let locations: HashMap<u64, &Location> =
+            profile.location.iter().map(|l| (l.id, l)).collect();
+
+        let mut cycles: HashMap<String, u64> = HashMap::new();
+        for sample in &profile.sample {
+            let Some(leaf) = sample.location_id.first().and_then(|id| locations.get(id)) else {
+                continue;
+            };
+            let profiled_name = leaf
+                .line
+                .first()
+                .and_then(|line| function_names.get(&line.function_id))
+                .copied()
+                .unwrap_or("");
+            // Mangled Rust symbols keep the profiler's demangled name
+            let name = match symbols.lookup(leaf.address) {
+                Some(symbol) if !is_mangled(symbol) => symbol.to_string(),
+                _ if !profiled_name.is_empty() => profiled_name.to_string(),
+                _ => format!("{:#x}", leaf.address),
+            };
+            let value = sample.value.first().copied().unwrap_or(0).max(0) as u64;
+            *cycles.entry(name).or_default() += value;
+        }
+
+        let mut functions: Vec<FunctionCycles> = cycles
+            .into_iter()
+            .map(|(name, cycles)| FunctionCycles {
+                category: Category::of(&name),
+                name,
+                cycles,
+            })
+            .collect();
+        functions.sort_by(|a, b| b.cycles.cmp(&a.cycles).then_with(|| a.name.cmp(&b.name)));
+        Ok(CycleProfile {
+            total_cycles: functions.iter().map(|f| f.cycles).sum(),
+            functions,
+        })
+    }
+
+    /// Cycles per category, in `Category::ALL` order
+    pub fn by_category(&self) -> Vec<(Category, u64)> {
+        Category::ALL
+            .iter()
+            .map(|&category| {
+                let cycles = self
+                    .functions
+                    .iter()
+                    .filter(|f| f.category == category)
+                    .map(|f| f.cycles)
+                    .sum();
+                (category, cycles)
+            })
+            .collect()
+    }
+}
+
+fn is_mangled(symbol: &str) -> bool {
+    symbol.starts_with("_ZN") || symbol.starts_with("_R")
+}
+
+/// Function symbols of the guest's user ELF as (start, size, name), sorted by address
+struct Symbols(Vec<(u64, u64, String)>);
+
+impl Symbols {
+    fn from_elf(elf: &[u8]) -> Result<Self> {
+        let user_elf = ProgramBinary::decode(elf)?.user_elf;
+        let file = ElfBytes::<LittleEndian>::minimal_parse(user_elf).context("parsing user ELF")?;
+        let mut symbols = Vec::new();
+        if let Some((symtab, strtab)) = file.symbol_table()? {
+            let defined_functions = symtab
+                .iter()
+                .filter(|sym| sym.st_symtype() == STT_FUNC && sym.st_shndx != SHN_UNDEF);
+            for sym in defined_functions {
+                let name = strtab.get(sym.st_name as usize)?;
+                symbols.push((sym.st_value, sym.st_size, name.to_string()));
+            }
+        }
+        symbols.sort();
+        Ok(Symbols(symbols))
+    }
+
+    /// The function containing `address`
+    fn lookup(&self, address: u64) -> Option<&str> {
+        let i = self.0.partition_point(|&(start, ..)| start <= address).checked_sub(1)?;
+        let (start, size, name) = &self.0[i];
+        // Size-less symbols (hand-written assembly) only match their entry address
+        (address < start + (*size).max(1)).then_some(name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories() {
+        let cases = [
+            ("[PageIn]", Category::ZkVm),
+            ("initialize_Init_Data", Category::ModuleInit),
+            ("_init_l_Eth2_processEpoch", Category::ModuleInit),
+            ("lean_alloc_small", Category::LeanRuntime),
+            ("lean::mpz::add", Category::LeanRuntime),
+            ("risc0_zkvm::guest::env::read", Category::ZkVm),
+            ("sys_read", Category::ZkVm),
+            ("_start", Category::ZkVm),
+            ("memcpy", Category::Libc),
+            ("__udivdi3", Category::Libc),
+            ("core::fmt::write", Category::Libc),
+            ("<alloc::vec::Vec<T> as core::clone::Clone>::clone", Category::Libc),
+            ("l_Eth2_processEpoch", Category::Guest),
+            ("guest_rust_eth2::transition::state_transition", Category::Guest),
+            ("memcpy_like", Category::Guest),
+        ];
+        for (name, category) in cases {
+            assert_eq!(Category::of(name), category, "{}", name);
+        }
+    }
+}
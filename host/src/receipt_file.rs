@@ -0,0 +1,221 @@
+//! Versioned on-disk container for receipts, so a receipt proven on one machine can be
+//! verified on another.
+//!
+//! Layout: the 8-byte magic `R0LEANRC`, the format version as u32 LE, then a
+//! bincode-encoded [`ReceiptHeader`] followed by the bincode-encoded `Receipt`.
+//! The header can be read and checked without decoding the much larger receipt.
+
+use anyhow::{bail, ensure, Context, Result};
+use bincode::Options;
+use risc0_zkvm::sha::{Digest, Digestible};
+use risc0_zkvm::Receipt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"R0LEANRC";
+
+/// Version written by [`write`]; [`read`] rejects any other
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Bounds on the encoded header and receipt, so a corrupt length prefix fails to decode
+/// instead of allocating without limit
+const MAX_HEADER_SIZE: u64 = 64 << 10;
+const MAX_RECEIPT_SIZE: u64 = 1 << 30;
+
+/// bincode 1.x's `serialize` encoding (fixed-width little-endian integers), with a size limit
+fn bincode_options(limit: u64) -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_limit(limit)
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptHeader {
+    /// Guest name, as accepted by `host --guest`
+    pub guest: String,
+    pub image_id: Digest,
+    /// risc0-zkvm version of the prover
+    pub risc0_version: String,
+    /// SHA-256 of the guest input: the u32 LE for the sum guests, the serialized
+    /// input for the eth2 guests
+    pub input_sha256: [u8; 32],
+}
+
+impl ReceiptHeader {
+    pub fn new(guest: &str, image_id: impl Into<Digest>, input: &[u8]) -> Self {
+        ReceiptHeader {
+            guest: guest.to_string(),
+            image_id: image_id.into(),
+            risc0_version: risc0_zkvm::VERSION.to_string(),
+            input_sha256: input_sha256(input),
+        }
+    }
+}
+
+pub fn input_sha256(input: &[u8]) -> [u8; 32] {
+    Sha256::digest(input).into()
+}
+
+pub fn write(path: &Path, header: &ReceiptHeader, receipt: &Receipt) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("creating receipt {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    write_to(&mut out, header, receipt)?;
+    out.flush()
+        .with_context(|| format!("writing receipt {}", path.display()))
+}
+
+fn write_to(out: &mut impl Write, header: &ReceiptHeader, receipt: &Receipt) -> Result<()> {
+    out.write_all(MAGIC)?;
+    out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    bincode_options(MAX_HEADER_SIZE)
+        .serialize_into(&mut *out, header)
+        .context("encoding receipt header")?;
+    bincode_options(MAX_RECEIPT_SIZE)
+        .serialize_into(&mut *out, receipt)
+        .context("encoding receipt")
+}
+
+/// Read only the header, checking magic and format version
+pub fn read_header(path: &Path) -> Result<ReceiptHeader> {
+    let mut input = open(path)?;
+    read_header_from(&mut input).with_context(|| format!("reading {}", path.display()))
+}
+
+/// Read header and receipt, and check that the receipt's claim names the header's image ID
+pub fn read(path: &Path) -> Result<(ReceiptHeader, Receipt)> {
+    let (header, receipt) = read_unchecked(path)?;
+    check_image_id(&header, &receipt).with_context(|| path.display().to_string())?;
+    Ok((header, receipt))
+}
+
+/// Read header and receipt without relating them; see [`check_image_id`]
+pub fn read_unchecked(path: &Path) -> Result<(ReceiptHeader, Receipt)> {
+    let mut input = open(path)?;
+    read_from(&mut input).with_context(|| format!("reading {}", path.display()))
+}
+
+/// Check that the receipt's claim names the header's image ID
+pub fn check_image_id(header: &ReceiptHeader, receipt: &Receipt) -> Result<()> {
+    let claim = receipt.claim()?.value().context("receipt claim is pruned")?;
+    let claimed_id = claim.pre.digest();
+    ensure!(
+        claimed_id == header.image_id,
+        "header names image ID {} but the receipt claims {}",
+        header.image_id,
+        claimed_id
+    );
+    Ok(())
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    let file =
+        File::open(path).with_context(|| format!("opening receipt {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+fn read_header_from(input: &mut impl Read) -> Result<ReceiptHeader> {
+    let mut magic = [0u8; 8];
+    input.read_exact(&mut magic).context("file too short")?;
+    ensure!(&magic == MAGIC, "not a receipt file (bad magic)");
+    let mut version = [0u8; 4];
+    input.read_exact(&mut version).context("file too short")?;
+    let version = u32::from_le_bytes(version);
+    if version != FORMAT_VERSION {
+        bail!(
+            "unsupported receipt format version {} (this build reads version {})",
+            version,
+            FORMAT_VERSION
+        );
+    }
+    bincode_options(MAX_HEADER_SIZE)
+        .deserialize_from(input)
+        .context("decoding receipt header")
+}
+
+fn read_from(input: &mut impl Read) -> Result<(ReceiptHeader, Receipt)> {
+    let header = read_header_from(&mut *input)?;
+    let receipt = bincode_options(MAX_RECEIPT_SIZE)
+        .deserialize_from(input)
+        .context("decoding receipt")?;
+    Ok((header, receipt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use risc0_zkvm::{FakeReceipt, InnerReceipt, ReceiptClaim};
+
+    fn receipt(image_id: Digest) -> Receipt {
+        let journal = vec![1, 2, 3, 4];
+        let claim = ReceiptClaim::ok(image_id, journal.clone());
+        Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal)
+    }
+
+    fn encode(header: &ReceiptHeader, receipt: &Receipt) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_to(&mut bytes, header, receipt).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let image_id = Digest::from([7u32; 8]);
+        let header = ReceiptHeader::new("eth2-rust", image_id, b"input");
+        let bytes = encode(&header, &receipt(image_id));
+        // Same encoding as bincode 1.x's `serialize`, which earlier builds wrote
+        let legacy = [
+            bincode::serialize(&header).unwrap(),
+            bincode::serialize(&receipt(image_id)).unwrap(),
+        ];
+        assert_eq!(bytes[12..], legacy.concat());
+
+        let (decoded, receipt) = read_from(&mut &bytes[..]).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(receipt.journal.bytes, [1, 2, 3, 4]);
+        check_image_id(&decoded, &receipt).unwrap();
+    }
+
+    #[test]
+    fn bad_magic() {
+        let image_id = Digest::from([7u32; 8]);
+        let header = ReceiptHeader::new("eth2-rust", image_id, b"");
+        let mut bytes = encode(&header, &receipt(image_id));
+        bytes[0] ^= 1;
+        let e = read_from(&mut &bytes[..]).unwrap_err();
+        assert!(e.to_string().contains("bad magic"), "{:#}", e);
+    }
+
+    #[test]
+    fn wrong_version() {
+        let image_id = Digest::from([7u32; 8]);
+        let header = ReceiptHeader::new("eth2-rust", image_id, b"");
+        let mut bytes = encode(&header, &receipt(image_id));
+        bytes[8..12].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        let e = read_from(&mut &bytes[..]).unwrap_err();
+        assert!(e.to_string().contains("unsupported receipt format version"), "{:#}", e);
+    }
+
+    #[test]
+    fn oversized_header_length() {
+        let image_id = Digest::from([7u32; 8]);
+        let header = ReceiptHeader::new("eth2-rust", image_id, b"");
+        let mut bytes = encode(&header, &receipt(image_id));
+        // The guest name's u64 length prefix
+        bytes[12..20].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(read_from(&mut &bytes[..]).is_err());
+    }
+
+    #[test]
+    fn image_id_mismatch() {
+        let header = ReceiptHeader::new("eth2-rust", Digest::from([7u32; 8]), b"");
+        let bytes = encode(&header, &receipt(Digest::from([8u32; 8])));
+        let (header, receipt) = read_from(&mut &bytes[..]).unwrap();
+        let e = check_image_id(&header, &receipt).unwrap_err();
+        assert!(e.to_string().contains("but the receipt claims"), "{:#}", e);
+    }
+}
@@ -0,0 +1,218 @@
+#include <lean/lean.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include <stddef.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <sys/time.h>
+#include <sys/types.h>
+
+// newlib's reentrant wrappers (_open_r, _read_r, ...) only pass errno on when it is set, so
+// a bare -1 reaches Lean as "success (error code: 0)". Every failure below sets errno.
+
+int _gettimeofday (struct timeval *__p, void *__tz) {
+  errno = ENOSYS;
+  return -1;
+}
+
+unsigned int __atomic_fetch_sub_4(volatile void *ptr, unsigned int val, int memorder)
+{
+    uint32_t* p = (uint32_t*)ptr;
+    unsigned int old = *p;
+    *p = old - val;
+    return old;
+}
+
+// Set by lean_risc0_build::Build::heap_size
+#ifndef SBRK_MAX_HEAP
+#define SBRK_MAX_HEAP 64*1024*1024
+#endif
+static unsigned char sbrk_heap[SBRK_MAX_HEAP];
+static ptrdiff_t sbrk_bkrp = 0;
+
+void* _sbrk (ptrdiff_t __incr) {
+    ptrdiff_t free = sbrk_bkrp;
+    sbrk_bkrp += __incr;
+    if(sbrk_bkrp > SBRK_MAX_HEAP) {
+        return NULL;
+    }
+    return &sbrk_heap[free];
+}
+
+// The part of the heap never handed out, which stays zero; heap snapshots skip it
+void lean_risc0_heap_unused(uintptr_t* start, uintptr_t* end) {
+    ptrdiff_t used = sbrk_bkrp < SBRK_MAX_HEAP ? sbrk_bkrp : SBRK_MAX_HEAP;
+    *start = (uintptr_t)&sbrk_heap[used];
+    *end = (uintptr_t)&sbrk_heap[SBRK_MAX_HEAP];
+}
+
+void _Unwind_Resume() {}
+void _Unwind_RaiseException() {}
+void _Unwind_Resume_or_Rethrow() {}
+void _Unwind_GetTextRelBase() {}
+void _Unwind_GetDataRelBase() {}
+void _Unwind_DeleteException() {}
+void _Unwind_GetRegionStart() {}
+void _Unwind_GetLanguageSpecificData() {}
+void _Unwind_GetIPInfo() {}
+void _Unwind_SetGR() {}
+void _Unwind_GetGR() {}
+void _Unwind_SetIP() {}
+
+int _kill(int pid, int sig) {
+    errno = ENOSYS;
+    return -1;
+}
+int _getpid() { return 1; }
+void _exit(int status) { while(1); }
+
+/* ── Files ──
+ * A read-only, in-memory filesystem holding the one character device Lean's runtime opens
+ * while initializing Init. fds 0-2 are the console: stdin is empty and stdout and stderr
+ * discard what they are given, since C code has no zkVM syscall to write. */
+
+// There is no entropy in the zkVM, and a proof must not depend on any: the device is a
+// fixed splitmix64 stream
+static uint64_t random_state = 0x4c65616e52697363;
+
+static ssize_t read_random(void* buf, size_t len) {
+    unsigned char* out = buf;
+    for (size_t i = 0; i < len; i += 8) {
+        uint64_t z = (random_state += 0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111eb;
+        z ^= z >> 31;
+        size_t n = len - i < 8 ? len - i : 8;
+        memcpy(out + i, &z, n);
+    }
+    return len;
+}
+
+struct device {
+    const char* path;
+    ssize_t (*read)(void* buf, size_t len);
+};
+
+// Init's IO.stdGenRef initializer seeds the standard generator with IO.getRandomBytes,
+// which the runtime reads from /dev/urandom; the guest gets the same, non-random bytes
+// on every run. Any other path fails with ENOENT.
+static const struct device devices[] = {
+    {"/dev/urandom", read_random},
+};
+
+#define CONSOLE_FDS 3
+#define MAX_OPEN 16
+static const struct device* open_devices[MAX_OPEN];
+
+// The device open as fd, or NULL with errno set; the console has none
+static const struct device* device_of(int fd) {
+    if (fd < CONSOLE_FDS || fd >= CONSOLE_FDS + MAX_OPEN || !open_devices[fd - CONSOLE_FDS]) {
+        errno = EBADF;
+        return NULL;
+    }
+    return open_devices[fd - CONSOLE_FDS];
+}
+
+static int is_console(int fd) { return fd >= 0 && fd < CONSOLE_FDS; }
+
+int _open(const char* path, int flags, int mode) {
+    const struct device* device = NULL;
+    for (size_t i = 0; i < sizeof(devices) / sizeof(devices[0]); i++) {
+        if (strcmp(path, devices[i].path) == 0) device = &devices[i];
+    }
+    if (!device) {
+        // Nothing can be created either
+        errno = (flags & O_CREAT) ? EROFS : ENOENT;
+        return -1;
+    }
+    if ((flags & O_ACCMODE) != O_RDONLY) {
+        errno = EROFS;
+        return -1;
+    }
+    for (int i = 0; i < MAX_OPEN; i++) {
+        if (!open_devices[i]) {
+            open_devices[i] = device;
+            return CONSOLE_FDS + i;
+        }
+    }
+    errno = EMFILE;
+    return -1;
+}
+
+int _close(int fd) {
+    if (is_console(fd)) return 0;
+    if (!device_of(fd)) return -1;
+    open_devices[fd - CONSOLE_FDS] = NULL;
+    return 0;
+}
+
+ssize_t _read(int fd, void* buf, size_t len) {
+    if (fd == 0) return 0;
+    if (is_console(fd)) {
+        errno = EBADF;
+        return -1;
+    }
+    const struct device* device = device_of(fd);
+    return device ? device->read(buf, len) : -1;
+}
+
+ssize_t _write(int fd, const void* buf, size_t len) {
+    if (fd == 1 || fd == 2) return len;
+    // stdin and the devices are open read-only
+    errno = EBADF;
+    return -1;
+}
+
+// Character devices have no file position; as on Linux, seeking them is a no-op
+off_t _lseek(int fd, off_t offset, int whence) {
+    if (!is_console(fd) && !device_of(fd)) return -1;
+    return 0;
+}
+
+int _fstat(int fd, struct stat* st) {
+    if (!is_console(fd) && !device_of(fd)) return -1;
+    memset(st, 0, sizeof(*st));
+    st->st_mode = S_IFCHR | (is_console(fd) ? 0620 : 0444);
+    return 0;
+}
+
+int _isatty(int fd) {
+    errno = (is_console(fd) || device_of(fd)) ? ENOTTY : EBADF;
+    return 0;
+}
+
+/* ── SHA-256 ──
+ * Lean's Eth2.sha256 is bound to lean_risc0_sha256, so the Lean guests hash with the zkVM
+ * SHA-256 accelerator like the Rust guest. The message is padded here and the whole buffer
+ * hashed in one sys_sha_buffer call (exported by risc0-zkvm-platform). */
+
+extern void sys_sha_buffer(uint32_t out_state[8], const uint32_t in_state[8],
+                           const uint8_t* buf, uint32_t count);
+
+// The accelerator keeps each state word in big-endian byte order
+static const uint32_t sha256_init[8] = {
+    0x67e6096a, 0x85ae67bb, 0x72f36e3c, 0x3af54fa5,
+    0x7f520e51, 0x8c68059b, 0xabd9831f, 0x19cde05b,
+};
+
+lean_obj_res lean_risc0_sha256(b_lean_obj_arg data) {
+    size_t len = lean_sarray_size(data);
+    // 0x80 marker and 8-byte bit length, rounded up to whole 64-byte blocks
+    size_t blocks = (len + 9 + 63) / 64;
+    uint32_t* words = calloc(blocks * 16, sizeof(uint32_t));
+    uint8_t* buf = (uint8_t*)words;
+    memcpy(buf, lean_sarray_cptr(data), len);
+    buf[len] = 0x80;
+    uint64_t bits = (uint64_t)len * 8;
+    for (int i = 0; i < 8; i++) {
+        buf[blocks * 64 - 1 - i] = (uint8_t)(bits >> (8 * i));
+    }
+    uint32_t state[8];
+    sys_sha_buffer(state, sha256_init, buf, (uint32_t)blocks);
+    free(words);
+    lean_object* out = lean_alloc_sarray(1, 32, 32);
+    memcpy(lean_sarray_cptr(out), state, 32);
+    return out;
+}
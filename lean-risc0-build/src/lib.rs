@@ -0,0 +1,500 @@
+//! Build-script helper for Lean guests.
+//!
+//! A Lean guest links the C that Lean generates for its project with the Lean RISC0 runtime
+//! and Init library from `LEAN_RISC0_PATH`, and with libc/libstdc++ from the RISC0 C
+//! toolchain at `RISC0_TOOLCHAIN_PATH`. It also compiles the zkVM libc shims (`c/shims.c`,
+//! shared by every Lean guest, which also bind Lean's SHA-256 to the zkVM accelerator) and
+//! the guest's own C entry wrapper.
+//!
+//! With [`Build::lean_project`] the build script runs `lake build` and compiles the C IR
+//! for riscv32im itself, rerunning whenever a `.lean` source changes, so `cargo build` alone
+//! produces an up-to-date guest. A guest's `build.rs` `main` is then:
+//!
+//! ```no_run
+//! lean_risc0_build::Build::new()
+//!     .lean_project("../../guest")
+//!     .file("risc0_lean.c")
+//!     .compile();
+//! ```
+//!
+//! [`Build::prune_init`] additionally initializes only the Init modules the guest reaches
+//! instead of all of Init: those defining a symbol its C IR references, and their imports.
+//!
+//! Without `lean_project`, prebuilt Lean libraries are linked from the guest's `lib/` directory.
+//! Every input is checked before anything is emitted, so a missing toolchain or library
+//! fails with a message saying what to set or run, not with a linker error.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod prune;
+
+const SHIMS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/c/shims.c");
+
+/// Why a Lean guest cannot be built
+#[derive(Debug)]
+pub enum Error {
+    /// A required environment variable is unset
+    MissingEnv { var: &'static str, hint: &'static str },
+    /// A path derived from the environment or the builder does not exist
+    MissingPath { path: PathBuf, hint: String },
+    /// `lake build` could not run or failed
+    Lake { project: PathBuf, detail: String },
+    /// A library or IR file could not be read for init pruning
+    Library { path: PathBuf, detail: String },
+    /// Builder options that cannot be combined
+    InvalidOptions(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingEnv { var, hint } => write!(f, "{} is not set; {}", var, hint),
+            Error::MissingPath { path, hint } => {
+                write!(f, "{} does not exist; {}", path.display(), hint)
+            }
+            Error::Lake { project, detail } => {
+                write!(f, "`lake build` in {} failed: {}", project.display(), detail)
+            }
+            Error::Library { path, detail } => {
+                write!(f, "cannot read {} for init pruning: {}", path.display(), detail)
+            }
+            Error::InvalidOptions(detail) => write!(f, "invalid build options: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Link and C-compile configuration of one Lean guest
+pub struct Build {
+    lean_project: Option<PathBuf>,
+    libs: Vec<String>,
+    lib_dir: PathBuf,
+    link_init: bool,
+    prune_init: bool,
+    files: Vec<PathBuf>,
+    heap_size: usize,
+    opt_level: String,
+    flags: Vec<String>,
+}
+
+impl Default for Build {
+    fn default() -> Self {
+        Build::new()
+    }
+}
+
+impl Build {
+    /// Links `lib/libGuest.a` with Init, a 64 MiB heap, `-O3 -DNDEBUG` and no extra C sources
+    pub fn new() -> Self {
+        Build {
+            lean_project: None,
+            libs: vec!["Guest".to_string()],
+            lib_dir: PathBuf::from("lib"),
+            link_init: true,
+            prune_init: false,
+            files: Vec::new(),
+            heap_size: 64 * 1024 * 1024,
+            opt_level: "3".to_string(),
+            flags: vec!["-DNDEBUG".to_string()],
+        }
+    }
+
+    /// Build the Lean project in `dir` (relative to the guest crate) with `lake build` and
+    /// compile its C IR into the guest, instead of linking prebuilt [`Build::lean_libs`].
+    /// `lake` comes from `PATH` unless `LAKE` is set.
+    pub fn lean_project(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.lean_project = Some(dir.into());
+        self
+    }
+
+    /// Prebuilt Lean libraries, linked from [`Build::lib_dir`] as `lib<name>.a`;
+    /// replaces the default `Guest`. Unused with [`Build::lean_project`].
+    pub fn lean_libs<I, S>(&mut self, libs: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.libs = libs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Directory of the guest's Lean libraries, relative to the guest crate (default `lib`)
+    pub fn lib_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.lib_dir = dir.into();
+        self
+    }
+
+    /// Link the Lean Init library (default true). Lean code that uses anything from the
+    /// prelude beyond unboxed scalars needs it.
+    pub fn link_init(&mut self, link_init: bool) -> &mut Self {
+        self.link_init = link_init;
+        self
+    }
+
+    /// Initialize only the Init modules the guest's code reaches, instead of all of Init
+    /// (default false). Needs [`Build::lean_project`] and Init. The guest's C wrapper is
+    /// compiled with `LEAN_RISC0_PRUNE_INIT` defined and must then initialize Init only
+    /// through `initialize_<Project>`, not by calling `initialize_Init` itself.
+    pub fn prune_init(&mut self, prune_init: bool) -> &mut Self {
+        self.prune_init = prune_init;
+        self
+    }
+
+    /// A C source compiled with the shims, relative to the guest crate
+    pub fn file(&mut self, file: impl Into<PathBuf>) -> &mut Self {
+        self.files.push(file.into());
+        self
+    }
+
+    /// Bytes the shims' `_sbrk` hands out to malloc (default 64 MiB)
+    pub fn heap_size(&mut self, bytes: usize) -> &mut Self {
+        self.heap_size = bytes;
+        self
+    }
+
+    /// C optimization level, as in `-O<level>` (default `3`)
+    pub fn opt_level(&mut self, level: impl Into<String>) -> &mut Self {
+        self.opt_level = level.into();
+        self
+    }
+
+    /// Extra C compiler flag, also used for the Lean C IR; unsupported flags are skipped
+    pub fn flag(&mut self, flag: impl Into<String>) -> &mut Self {
+        self.flags.push(flag.into());
+        self
+    }
+
+    /// Validate the inputs, compile the C sources and emit the link directives.
+    /// Panics with the validation error, as `cc::Build::compile` does.
+    pub fn compile(&self) {
+        if let Err(e) = self.try_compile() {
+            panic!("cannot build Lean guest: {}", e);
+        }
+    }
+
+    pub fn try_compile(&self) -> Result<(), Error> {
+        let paths = self.validate()?;
+
+        println!("cargo::rerun-if-env-changed=LEAN_RISC0_PATH");
+        println!("cargo::rerun-if-env-changed=RISC0_TOOLCHAIN_PATH");
+        println!("cargo::rerun-if-changed={}", SHIMS);
+        for file in &paths.files {
+            println!("cargo::rerun-if-changed={}", file.display());
+        }
+
+        match &paths.lean_project {
+            Some(project) => self.compile_lean_project(project, &paths)?,
+            None => {
+                println!("cargo::rerun-if-changed={}", paths.lib_dir.display());
+                println!("cargo:rustc-link-search=native={}", paths.lib_dir.display());
+                for lib in &self.libs {
+                    println!("cargo:rustc-link-lib=static={}", lib);
+                }
+            }
+        }
+        println!("cargo:rustc-link-search=native={}", paths.lean_lib_dir.display());
+        println!("cargo:rustc-link-search=native={}", paths.toolchain_lib_dir.display());
+        println!("cargo:rustc-link-lib=static=c");
+        println!("cargo:rustc-link-lib=static=stdc++");
+        println!("cargo:rustc-link-lib=static=Lean");
+        if self.link_init {
+            println!("cargo:rustc-link-lib=static=Init");
+        }
+        // The shims replace libc's syscall stubs (_sbrk, _write, ...)
+        println!("cargo::rustc-link-arg-bins=--allow-multiple-definition");
+        println!("cargo::rustc-link-arg-bins=--error-limit=0");
+
+        let mut build = paths.c_build();
+        build
+            .include(&paths.include_dir)
+            .file(SHIMS)
+            .files(&paths.files)
+            .define("SBRK_MAX_HEAP", self.heap_size.to_string().as_str())
+            .flag_if_supported(format!("-O{}", self.opt_level));
+        if self.prune_init {
+            build.define("LEAN_RISC0_PRUNE_INIT", None);
+        }
+        for flag in &self.flags {
+            build.flag_if_supported(flag);
+        }
+        build.compile("c_risc0_lean");
+        Ok(())
+    }
+
+    /// Run `lake build` and compile the C IR of the project and its packages into `libGuest.a`
+    fn compile_lean_project(&self, project: &Path, paths: &Paths) -> Result<(), Error> {
+        // Sources only: lake writes under .lake, which would rerun the build script every time
+        let mut sources = Vec::new();
+        collect_lean_sources(project, &mut sources);
+        for file in &sources {
+            println!("cargo::rerun-if-changed={}", file.display());
+        }
+        println!("cargo::rerun-if-env-changed=LAKE");
+
+        // risc0-build builds guests one at a time, so guests sharing a project never run
+        // lake concurrently
+        let lake = env::var_os("LAKE").map_or_else(|| PathBuf::from("lake"), PathBuf::from);
+        let lake_error = |detail: String| Error::Lake {
+            project: project.to_path_buf(),
+            detail,
+        };
+        let output = Command::new(&lake)
+            .arg("build")
+            .current_dir(project)
+            .output()
+            .map_err(|e| {
+                lake_error(format!(
+                    "cannot run {}: {}; install Lean with elan or set LAKE",
+                    lake.display(),
+                    e
+                ))
+            })?;
+        if !output.status.success() {
+            return Err(lake_error(format!(
+                "{}\n{}{}",
+                output.status,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let mut ir = Vec::new();
+        collect_ir(project, &mut ir);
+        if let Ok(packages) = fs::read_dir(project.join(".lake/packages")) {
+            for package in packages.flatten() {
+                collect_ir(&package.path(), &mut ir);
+            }
+        }
+        if ir.is_empty() {
+            return Err(Error::MissingPath {
+                path: project.join(".lake/build/ir"),
+                hint: "lake build produced no C; check the lakefile's lean_lib targets".to_string(),
+            });
+        }
+        ir.sort();
+
+        let mut build = paths.c_build();
+        if self.prune_init {
+            let init_lib = paths.lean_lib_dir.join("libInit.a");
+            println!("cargo::rerun-if-changed={}", init_lib.display());
+            let runtime_lib = paths.lean_lib_dir.join("libLean.a");
+            let plan = prune::InitPlan::new(&init_lib, &runtime_lib, &ir)?;
+            let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR is set for build scripts");
+            let pruned = Path::new(&out_dir).join("init_pruned.c");
+            fs::write(&pruned, plan.to_c()).map_err(|e| Error::Library {
+                path: pruned.clone(),
+                detail: e.to_string(),
+            })?;
+            build.file(pruned).define("initialize_Init", prune::PRUNED_INIT);
+        }
+        build
+            .include(&paths.include_dir)
+            .files(&ir)
+            .std("gnu11")
+            .flag("-ffreestanding")
+            .flag("-fno-builtin")
+            .flag(format!("-O{}", self.opt_level))
+            // Generated code; its warnings are not actionable
+            .warnings(false)
+            .cargo_warnings(false);
+        for flag in &self.flags {
+            build.flag_if_supported(flag);
+        }
+        build.compile("Guest");
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<Paths, Error> {
+        let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+
+        let lean_risc0 = env_path(
+            "LEAN_RISC0_PATH",
+            "point it at the Lean RISC0 runtime install, typically $HOME/.lean-risc0",
+        )?;
+        let include_dir = lean_risc0.join("include");
+        let lean_lib_dir = lean_risc0.join("lib");
+        let runtime_hint = "install the Lean RISC0 runtime (https://github.com/anoma/lean-risc0-runtime)";
+        require(&include_dir.join("lean").join("lean.h"), runtime_hint)?;
+        require(&lean_lib_dir.join("libLean.a"), runtime_hint)?;
+        if self.link_init {
+            require(
+                &lean_lib_dir.join("libInit.a"),
+                "install the Lean RISC0 Init library (https://github.com/anoma/lean-risc0-init)",
+            )?;
+        }
+
+        let toolchain = env_path(
+            "RISC0_TOOLCHAIN_PATH",
+            "point it at the RISC0 C toolchain, typically \
+             $HOME/.risc0/toolchains/<version>-cpp-<host>/riscv32im-<host>",
+        )?;
+        let toolchain_lib_dir = toolchain.join("riscv32-unknown-elf").join("lib");
+        let toolchain_bin_dir = toolchain.join("bin");
+        let toolchain_hint = "RISC0_TOOLCHAIN_PATH must be the riscv32im C toolchain (`rzup install cpp`)";
+        require(&toolchain_lib_dir.join("libc.a"), toolchain_hint)?;
+        require(&toolchain_lib_dir.join("libstdc++.a"), toolchain_hint)?;
+        require(&toolchain_bin_dir.join("riscv32-unknown-elf-gcc"), toolchain_hint)?;
+
+        if self.prune_init && (self.lean_project.is_none() || !self.link_init) {
+            return Err(Error::InvalidOptions("prune_init needs lean_project and link_init"));
+        }
+
+        let lib_dir = manifest_dir.join(&self.lib_dir);
+        let lean_project = match &self.lean_project {
+            Some(dir) => {
+                let project = manifest_dir.join(dir);
+                let has_lakefile = ["lakefile.toml", "lakefile.lean"]
+                    .iter()
+                    .any(|name| project.join(name).exists());
+                if !has_lakefile {
+                    return Err(Error::MissingPath {
+                        path: project.join("lakefile.toml"),
+                        hint: "Build::lean_project must point at a Lake project".to_string(),
+                    });
+                }
+                Some(project)
+            }
+            None => {
+                for lib in &self.libs {
+                    require(
+                        &lib_dir.join(format!("lib{}.a", lib)),
+                        "build the Lean library and copy it here, or use Build::lean_project",
+                    )?;
+                }
+                None
+            }
+        };
+
+        let files = self
+            .files
+            .iter()
+            .map(|file| {
+                let path = manifest_dir.join(file);
+                require(&path, "check the path passed to Build::file")?;
+                Ok(path)
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Paths {
+            include_dir,
+            lean_lib_dir,
+            toolchain_lib_dir,
+            toolchain_bin_dir,
+            lib_dir,
+            lean_project,
+            files,
+        })
+    }
+}
+
+/// Absolute inputs of a validated build
+struct Paths {
+    include_dir: PathBuf,
+    lean_lib_dir: PathBuf,
+    toolchain_lib_dir: PathBuf,
+    toolchain_bin_dir: PathBuf,
+    lib_dir: PathBuf,
+    lean_project: Option<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+impl Paths {
+    /// A C build using the riscv32im compiler of the validated toolchain
+    fn c_build(&self) -> cc::Build {
+        let mut build = cc::Build::new();
+        build
+            .compiler(self.toolchain_bin_dir.join("riscv32-unknown-elf-gcc"))
+            .archiver(self.toolchain_bin_dir.join("riscv32-unknown-elf-ar"))
+            .flag("-march=rv32im")
+            .flag("-mabi=ilp32");
+        build
+    }
+}
+
+/// Every file under `dir` with extension `ext`, recursively
+fn collect_files(dir: &Path, ext: &str, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, ext, out);
+        } else if path.extension().is_some_and(|e| e == ext) {
+            out.push(path);
+        }
+    }
+}
+
+/// The C IR of a Lake package, skipping files whose module has no `.lean` source: lake
+/// leaves the IR of deleted or renamed modules behind, and linking it would bring their
+/// initializers and symbols into the guest
+fn collect_ir(package: &Path, out: &mut Vec<PathBuf>) {
+    let ir_dir = package.join(".lake/build/ir");
+    let mut files = Vec::new();
+    collect_files(&ir_dir, "c", &mut files);
+    if files.is_empty() {
+        return;
+    }
+    let mut sources = Vec::new();
+    collect_lean_sources(package, &mut sources);
+    for file in files {
+        // The IR mirrors the module path; the source may sit under the package's srcDir
+        let module = file.strip_prefix(&ir_dir).unwrap_or(&file).with_extension("lean");
+        if sources.iter().any(|source| source.ends_with(&module)) {
+            out.push(file);
+        } else {
+            println!(
+                "cargo::warning=skipping {}: no {} in {}; stale IR of a removed module?",
+                file.display(),
+                module.display(),
+                package.display()
+            );
+        }
+    }
+}
+
+/// The `.lean` sources and Lake configuration of a project, skipping `.lake` and other
+/// hidden directories
+fn collect_lean_sources(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_dir() {
+            if !name.starts_with('.') {
+                collect_lean_sources(&path, out);
+            }
+        } else if name.ends_with(".lean")
+            || ["lakefile.toml", "lean-toolchain", "lake-manifest.json"].contains(&name.as_ref())
+        {
+            out.push(path);
+        }
+    }
+}
+
+fn env_path(var: &'static str, hint: &'static str) -> Result<PathBuf, Error> {
+    match env::var_os(var) {
+        Some(value) if !value.is_empty() => {
+            let path = PathBuf::from(value);
+            require(&path, &format!("check {}", var))?;
+            Ok(path)
+        }
+        _ => Err(Error::MissingEnv { var, hint }),
+    }
+}
+
+fn require(path: &Path, hint: &str) -> Result<(), Error> {
+    if path.exists() {
+        Ok(())
+    } else {
+        Err(Error::MissingPath {
+            path: path.to_path_buf(),
+            hint: hint.to_string(),
+        })
+    }
+}
@@ -0,0 +1,252 @@
+//! Init pruning: initialize only the Init modules a guest can reach.
+//!
+//! Every Lean module imports `Init` implicitly, so `initialize_Guest` calls `initialize_Init`,
+//! which initializes all of Init (~400 modules). The guest only needs the modules whose
+//! definitions it uses, and whatever those import. The pruned initializer replaces the
+//! `initialize_Init` call in the guest's IR with calls to exactly those modules:
+//!
+//! - roots: the Init modules defining a symbol the guest's IR references (including
+//!   `initialize_*` calls for explicit `import Init.*`), or one the Lean runtime references
+//! - edges: the `initialize_*` symbols each `libInit.a` member calls, i.e. its imports
+//!
+//! Modules are initialized imports first, as Lean's own initializers do.
+
+use crate::Error;
+use elf::abi::{SHN_UNDEF, STB_GLOBAL, STB_WEAK};
+use elf::endian::AnyEndian;
+use elf::ElfBytes;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Initializer of the `Init` module itself, which imports all of Init
+const INIT: &str = "initialize_Init";
+
+/// Symbol the guest's IR calls instead of [`INIT`] when pruning
+pub(crate) const PRUNED_INIT: &str = "lean_risc0_initialize_Init_pruned";
+
+/// The Init modules a guest reaches, by initializer symbol, in initialization order
+pub(crate) struct InitPlan {
+    pub(crate) modules: Vec<String>,
+    /// Modules in `libInit.a`, including the `Init` module itself
+    pub(crate) total: usize,
+}
+
+impl InitPlan {
+    pub(crate) fn new(init_lib: &Path, runtime_lib: &Path, ir: &[PathBuf]) -> Result<Self, Error> {
+        let init = read_objects(init_lib)?;
+
+        // Module of every symbol libInit.a defines, and the modules each module imports
+        let mut owner: HashMap<&str, &str> = HashMap::new();
+        let mut imports: HashMap<&str, Vec<&str>> = HashMap::new();
+        for object in &init {
+            let Some(module) = object.defined.iter().find(|s| s.starts_with("initialize_")) else {
+                continue;
+            };
+            for symbol in &object.defined {
+                owner.insert(symbol, module);
+            }
+            let called = object.undefined.iter().filter(|s| s.starts_with("initialize_"));
+            imports.insert(module, called.map(String::as_str).collect());
+        }
+        if !imports.contains_key(INIT) {
+            return Err(Error::Library {
+                path: init_lib.to_path_buf(),
+                detail: format!("no member defines {}; not a Lean Init library", INIT),
+            });
+        }
+
+        let mut roots = HashSet::new();
+        for file in ir {
+            let text = fs::read_to_string(file).map_err(|e| Error::Library {
+                path: file.clone(),
+                detail: e.to_string(),
+            })?;
+            let identifiers = text
+                .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .filter(|s| !s.is_empty());
+            roots.extend(identifiers.filter_map(|s| owner.get(s).copied()));
+        }
+        for object in read_objects(runtime_lib)? {
+            roots.extend(object.undefined.iter().filter_map(|s| owner.get(s.as_str()).copied()));
+        }
+        roots.remove(INIT);
+        let mut roots: Vec<&str> = roots.into_iter().collect();
+        roots.sort_unstable();
+
+        Ok(InitPlan {
+            modules: init_order(&roots, &imports),
+            total: imports.len(),
+        })
+    }
+
+    /// C source defining [`PRUNED_INIT`]
+    pub(crate) fn to_c(&self) -> String {
+        let mut c = format!(
+            "/* Generated by lean-risc0-build: the guest's initialize_Init, pruned to the\n \
+             * {} of {} Init modules it reaches, imports first. */\n\
+             #include <lean/lean.h>\n\n",
+            self.modules.len(),
+            self.total
+        );
+        for module in &self.modules {
+            writeln!(c, "lean_object* {}(uint8_t builtin, lean_object* w);", module).unwrap();
+        }
+        writeln!(c, "\nlean_object* {}(uint8_t builtin, lean_object* w) {{", PRUNED_INIT).unwrap();
+        c.push_str("    lean_object* res;\n");
+        // As in Lean's own initializers, the first failure is the result
+        for module in &self.modules {
+            writeln!(c, "    res = {}(builtin, lean_io_mk_world());", module).unwrap();
+            c.push_str("    if (lean_io_result_is_error(res)) return res;\n");
+            c.push_str("    lean_dec_ref(res);\n");
+        }
+        c.push_str("    return lean_io_result_mk_ok(lean_box(0));\n}\n");
+        c
+    }
+}
+
+/// `roots` and every module they import, transitively, with imports before importers;
+/// the import graph is acyclic
+fn init_order(roots: &[&str], imports: &HashMap<&str, Vec<&str>>) -> Vec<String> {
+    fn visit<'a>(
+        module: &'a str,
+        imports: &HashMap<&'a str, Vec<&'a str>>,
+        seen: &mut HashSet<&'a str>,
+        order: &mut Vec<String>,
+    ) {
+        if module == INIT || !seen.insert(module) {
+            return;
+        }
+        for &import in imports.get(module).into_iter().flatten() {
+            visit(import, imports, seen, order);
+        }
+        order.push(module.to_string());
+    }
+    let mut seen = HashSet::new();
+    let mut order = Vec::new();
+    for &root in roots {
+        visit(root, imports, &mut seen, &mut order);
+    }
+    order
+}
+
+/// Global symbols of one archive member
+struct Object {
+    defined: Vec<String>,
+    undefined: Vec<String>,
+}
+
+fn read_objects(path: &Path) -> Result<Vec<Object>, Error> {
+    let error = |detail: String| Error::Library {
+        path: path.to_path_buf(),
+        detail,
+    };
+    let data = fs::read(path).map_err(|e| error(e.to_string()))?;
+    let members = archive_members(&data).ok_or_else(|| error("malformed archive".to_string()))?;
+    let mut objects = Vec::new();
+    for member in members.into_iter().filter(|m| m.starts_with(b"\x7fELF")) {
+        let file = ElfBytes::<AnyEndian>::minimal_parse(member).map_err(|e| error(e.to_string()))?;
+        let Some((symtab, strtab)) = file.symbol_table().map_err(|e| error(e.to_string()))? else {
+            continue;
+        };
+        let mut object = Object {
+            defined: Vec::new(),
+            undefined: Vec::new(),
+        };
+        for sym in symtab.iter() {
+            let bind = sym.st_bind();
+            if sym.st_name == 0 || (bind != STB_GLOBAL && bind != STB_WEAK) {
+                continue;
+            }
+            let name = strtab.get(sym.st_name as usize).map_err(|e| error(e.to_string()))?;
+            if sym.st_shndx == SHN_UNDEF {
+                object.undefined.push(name.to_string());
+            } else {
+                object.defined.push(name.to_string());
+            }
+        }
+        objects.push(object);
+    }
+    Ok(objects)
+}
+
+/// Contents of the members of a `!<arch>` static library, without the symbol index and the
+/// GNU long-name table
+fn archive_members(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut rest = data.strip_prefix(b"!<arch>\n")?;
+    let mut members = Vec::new();
+    while !rest.is_empty() {
+        let (header, body) = rest.split_at_checked(60)?;
+        let name = std::str::from_utf8(&header[..16]).ok()?.trim_end();
+        let size: usize = std::str::from_utf8(&header[48..58]).ok()?.trim().parse().ok()?;
+        let member = body.get(..size)?;
+        match name {
+            "/" | "//" | "/SYM64/" => {}
+            // BSD long names precede the contents
+            _ if name.starts_with("#1/") => {
+                let name_len: usize = name[3..].parse().ok()?;
+                members.push(member.get(name_len..)?);
+            }
+            _ => members.push(member),
+        }
+        // Members are 2-byte aligned
+        rest = body.get((size + size % 2).min(body.len())..)?;
+    }
+    Some(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_come_first() {
+        let imports = HashMap::from([
+            (INIT, vec!["initialize_Init_Data", "initialize_Init_Prelude"]),
+            ("initialize_Init_Prelude", vec![]),
+            ("initialize_Init_Core", vec!["initialize_Init_Prelude"]),
+            ("initialize_Init_Data", vec!["initialize_Init_Core", "initialize_Init_Prelude"]),
+            ("initialize_Init_System_IO", vec!["initialize_Init_Data", INIT]),
+        ]);
+        let order = init_order(&["initialize_Init_Core", "initialize_Init_System_IO"], &imports);
+        assert_eq!(
+            order,
+            [
+                "initialize_Init_Prelude",
+                "initialize_Init_Core",
+                "initialize_Init_Data",
+                "initialize_Init_System_IO",
+            ]
+        );
+    }
+
+    fn member(name: &str, contents: &[u8]) -> Vec<u8> {
+        let mut out = format!("{:<16}{:<32}{:<10}`\n", name, "0", contents.len()).into_bytes();
+        out.extend_from_slice(contents);
+        if contents.len() % 2 == 1 {
+            out.push(b'\n');
+        }
+        out
+    }
+
+    #[test]
+    fn archive_members_skip_the_indexes() {
+        let mut archive = b"!<arch>\n".to_vec();
+        archive.extend(member("/", b"symbol index"));
+        archive.extend(member("//", b"long_name.o/\n"));
+        archive.extend(member("a.o/", b"odd"));
+        archive.extend(member("/0", b"long"));
+        archive.extend(member("#1/6", b"bsd.o\0body"));
+        let members = archive_members(&archive).unwrap();
+        assert_eq!(members, [&b"odd"[..], b"long", b"body"]);
+    }
+
+    #[test]
+    fn archive_members_reject_malformed() {
+        assert!(archive_members(b"not an archive").is_none());
+        let mut truncated = b"!<arch>\n".to_vec();
+        truncated.extend(&member("a.o/", b"contents")[..64]);
+        assert!(archive_members(&truncated).is_none());
+    }
+}
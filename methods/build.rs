@@ -1,3 +1,10 @@
+mod snapshot;
+
 fn main() {
-    risc0_build::embed_methods();
+    let guests = risc0_build::embed_methods();
+    let init = guests
+        .iter()
+        .find(|guest| guest.name == "guest-eth2-init-snapshot")
+        .expect("guest-eth2-init-snapshot is a method");
+    snapshot::embed("GUEST_ETH2_SNAPSHOT", init);
 }
@@ -0,0 +1,53 @@
+#![no_main]
+risc0_zkvm::guest::entry!(main);
+
+use eth2_journal::{Eth2AggregateInput, Eth2AggregateJournal, Eth2Status};
+use risc0_zkvm::guest::env;
+use risc0_zkvm::serde::to_vec;
+
+// Compose consecutive eth2 receipts into one proof of the whole span.
+// Each journal is checked against a receipt the host attached with add_assumption.
+fn main() {
+    let input: Eth2AggregateInput = env::read();
+    let (Some(first), Some(last)) = (input.journals.first(), input.journals.last()) else {
+        panic!("no receipts to aggregate")
+    };
+
+    for (i, journal) in input.journals.iter().enumerate() {
+        assert!(
+            journal.status == Eth2Status::Ok,
+            "receipt {} did not apply its blocks",
+            i
+        );
+        // A zero root is a stub's placeholder (the Lean guests'), and would chain to anything
+        assert!(
+            journal.pre_state_root != [0; 32] && journal.post_state_root != [0; 32],
+            "receipt {} commits a zero state root",
+            i
+        );
+        env::verify(input.image_id, &to_vec(journal).unwrap()).unwrap();
+    }
+    for (i, pair) in input.journals.windows(2).enumerate() {
+        assert!(
+            pair[0].post_state_root == pair[1].pre_state_root,
+            "receipt {} does not start from the post-state of receipt {}",
+            i + 1,
+            i
+        );
+        assert!(
+            pair[0].slot < pair[1].slot,
+            "receipt {} does not advance past slot {}",
+            i + 1,
+            pair[0].slot
+        );
+    }
+
+    env::commit(&Eth2AggregateJournal {
+        image_id: input.image_id,
+        pre_state_root: first.pre_state_root,
+        post_state_root: last.post_state_root,
+        slot: last.slot,
+        blocks: input.journals.iter().map(|j| j.blocks_applied).sum(),
+        receipts: input.journals.len() as u32,
+    });
+}
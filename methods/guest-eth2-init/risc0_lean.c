@@ -1,53 +1,108 @@
 /**
  * ETH2 STF guest wrapper — WITH Init initialization.
  *
- * Workaround: initialize_Init_Data() fails in zkVM (strerror(0) returns
- * "success" but the init function treats errno=0 as error).  Pre-calling
- * it sets _G_initialized=true so that initialize_Init() skips it and succeeds.
+ * If an initializer fails, the STF is not run and lean_eth2_init_error() names the
+ * initializer and its IO error; the Rust wrapper commits it as a Stage::Init error.
+ *
+ * With the prune-init feature (LEAN_RISC0_PRUNE_INIT), initialize_Guest()
+ * initializes only the Init modules the guest reaches, and step 2 is skipped.
+ *
+ * With the heap-snapshot feature (LEAN_RISC0_HEAP_SNAPSHOT), the host runs steps 1-3
+ * once at build time and embeds the resulting memory in the ELF (methods/snapshot.rs),
+ * so the snapshot ELF starts with lean_eth2_snapshot_restored set and skips them.
  */
 #include <lean/lean.h>
+#include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
 
 extern lean_object* lean_initialize_runtime_module(lean_object*);
-extern lean_object* initialize_Init_Data(uint8_t, lean_object*);
 extern lean_object* initialize_Init(uint8_t, lean_object*);
 extern lean_object* initialize_Guest(uint8_t, lean_object*);
+extern lean_object* lean_io_error_to_string(lean_object*);
 extern lean_object* risc0_main_eth2(lean_object*);
 
+/* "<initializer>: <IO error>" of the first failed initializer */
+static char init_error[256];
+
+/* Record a failed initializer's error; returns whether res is ok */
+static int init_ok(const char* initializer, lean_object* res) {
+    if (lean_io_result_is_ok(res)) {
+        lean_dec_ref(res);
+        return 1;
+    }
+    lean_object* err = lean_io_result_get_error(res);
+    lean_inc(err);
+    lean_object* msg = lean_io_error_to_string(err);
+    snprintf(init_error, sizeof(init_error), "%s: %s", initializer, lean_string_cstr(msg));
+    lean_dec_ref(msg);
+    lean_dec_ref(res);
+    return 0;
+}
+
+/* Steps 1-3: runtime, Init and Guest module initialization, stopping at the first failure */
+static void initialize(void) {
+    /* Step 1: Initialize runtime */
+    if (!init_ok("lean_initialize_runtime_module",
+                 lean_initialize_runtime_module(lean_io_mk_world())))
+        return;
+
+#ifndef LEAN_RISC0_PRUNE_INIT
+    /* Step 2: Initialize Init */
+    if (!init_ok("initialize_Init", initialize_Init(1, lean_io_mk_world()))) return;
+#endif
+
+    /* Step 3: Initialize Guest */
+    init_ok("initialize_Guest", initialize_Guest(1, lean_io_mk_world()));
+}
+
 /**
- * Entry point called from Rust guest via FFI.
+ * Why initialization failed, or NULL if it succeeded.
  */
-void lean_eth2_init_entry(const uint8_t* input, size_t input_len,
-                           uint8_t** output, size_t* output_len) {
-    /* Step 1: Initialize runtime */
-    lean_object* res = lean_initialize_runtime_module(lean_io_mk_world());
-    if (lean_io_result_is_ok(res)) lean_dec_ref(res);
+const char* lean_eth2_init_error(void) {
+    return init_error[0] ? init_error : NULL;
+}
 
-    /* Step 2: Workaround — pre-call Init_Data (fails but sets _G_initialized) */
-    res = initialize_Init_Data(1, lean_io_mk_world());
-    /* Ignore result — failure is expected */
+#ifdef LEAN_RISC0_HEAP_SNAPSHOT
+/* Set only in the memory captured for the snapshot ELF */
+int lean_eth2_snapshot_restored = 0;
 
-    /* Step 3: Full Init (succeeds because Data flag is already set) */
-    res = initialize_Init(1, lean_io_mk_world());
-    if (lean_io_result_is_ok(res)) lean_dec_ref(res);
+/**
+ * Build-time capture: initialize, then mark the memory about to be captured as initialized.
+ */
+void lean_eth2_snapshot_initialize(void) {
+    initialize();
+    lean_eth2_snapshot_restored = 1;
+}
+#endif
 
-    /* Step 4: Initialize Guest */
-    res = initialize_Guest(1, lean_io_mk_world());
-    if (lean_io_result_is_ok(res)) lean_dec_ref(res);
+/**
+ * Entry point called from Rust guest via FFI.
+ */
+void lean_eth2_init_entry(const uint8_t* input, size_t input_len,
+                           uint8_t** output, size_t* output_len) {
+#ifdef LEAN_RISC0_HEAP_SNAPSHOT
+    if (!lean_eth2_snapshot_restored) initialize();
+#else
+    initialize();
+#endif
+    if (init_error[0]) {
+        *output = NULL;
+        *output_len = 0;
+        return;
+    }
 
-    /* Step 5: Create ByteArray from real input and call risc0_main_eth2 */
+    /* Step 4: Create ByteArray from real input and call risc0_main_eth2 */
     lean_object* lean_input = lean_alloc_sarray(1, input_len, input_len);
     memcpy(lean_sarray_cptr(lean_input), input, input_len);
 
     lean_object* lean_result = risc0_main_eth2(lean_input);
 
-    /* Step 6: Return result */
+    /* Step 5: Return result */
     if (lean_result == NULL || lean_is_scalar(lean_result)) {
-        /* Error fallback */
-        static uint8_t err_buf[2] = {0xDE, 0xAD};
-        *output = err_buf;
-        *output_len = 2;
+        /* No result: the Rust wrapper reports a runtime error */
+        *output = NULL;
+        *output_len = 0;
     } else {
         *output_len = lean_sarray_size(lean_result);
         *output = (uint8_t*)lean_sarray_cptr(lean_result);
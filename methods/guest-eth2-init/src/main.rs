@@ -1,7 +1,9 @@
 #![no_main]
 risc0_zkvm::guest::entry!(main);
 
-use risc0_zkvm::guest::env;
+use eth2_journal::{decode_lean_output, Eth2Error, Eth2Journal, Eth2Status, Stage, POST_STATE_FD};
+use risc0_zkvm::guest::env::{self, FdWriter, Write};
+use std::ffi::{c_char, CStr};
 
 extern "C" {
     fn lean_eth2_init_entry(
@@ -10,10 +12,29 @@ extern "C" {
         output: *mut *mut u8,
         output_len: *mut usize,
     );
+    fn lean_eth2_init_error() -> *const c_char;
+    #[cfg(feature = "heap-snapshot")]
+    fn lean_eth2_snapshot_initialize();
+    #[cfg(feature = "heap-snapshot")]
+    fn lean_risc0_heap_unused(start: *mut usize, end: *mut usize);
 }
 
+/// Nonzero only in the heap-snapshot-capture build, which methods/snapshot.rs runs to take
+/// the snapshot. The snapshot ELF is the build without it, so no input can capture there.
+/// Read volatile, so both builds compile to the same code and the captured memory fits either.
+#[cfg(feature = "heap-snapshot")]
+#[no_mangle]
+static LEAN_ETH2_SNAPSHOT_CAPTURE: u8 = cfg!(feature = "heap-snapshot-capture") as u8;
+
 fn main() {
     let input: Vec<u8> = env::read();
+    #[cfg(feature = "heap-snapshot")]
+    if unsafe { std::ptr::read_volatile(&LEAN_ETH2_SNAPSHOT_CAPTURE) } != 0 {
+        if let Some(regions) = input.strip_prefix(eth2_journal::HEAP_SNAPSHOT_REQUEST) {
+            capture_heap_snapshot(regions);
+            return;
+        }
+    }
     let mut output_ptr: *mut u8 = std::ptr::null_mut();
     let mut output_len: usize = 0;
 
@@ -26,6 +47,45 @@ fn main() {
         );
     }
 
-    let result = unsafe { std::slice::from_raw_parts(output_ptr, output_len) };
-    env::commit_slice(result);
+    let result = if output_ptr.is_null() {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(output_ptr, output_len) }
+    };
+    let (journal, post_state) = decode_lean_output(result).unwrap_or_else(|| {
+        let init_error = unsafe { lean_eth2_init_error() };
+        let error = if init_error.is_null() {
+            Eth2Error::new(Stage::Runtime, "Lean returned no decodable output")
+        } else {
+            Eth2Error::new(Stage::Init, &unsafe { CStr::from_ptr(init_error) }.to_string_lossy())
+        };
+        (Eth2Journal::new(Eth2Status::Error(error)), &[][..])
+    });
+    if !post_state.is_empty() {
+        FdWriter::new(POST_STATE_FD, |_| {}).write_slice(post_state);
+    }
+    env::commit(&journal);
+}
+
+/// Build-time snapshot: initialize Lean, then write each requested region to the host as a
+/// record, skipping the heap's unused tail, which is still zero
+#[cfg(feature = "heap-snapshot")]
+fn capture_heap_snapshot(regions: &[u8]) {
+    let (mut unused_start, mut unused_end) = (0, 0);
+    unsafe {
+        lean_eth2_snapshot_initialize();
+        lean_risc0_heap_unused(&mut unused_start, &mut unused_end);
+    }
+    let mut writer = FdWriter::new(eth2_journal::HEAP_SNAPSHOT_FD, |_| {});
+    for region in regions.chunks_exact(8) {
+        let start = u32::from_le_bytes(region[..4].try_into().unwrap()) as usize;
+        let end = start + u32::from_le_bytes(region[4..].try_into().unwrap()) as usize;
+        for (start, end) in [(start, end.min(unused_start)), (start.max(unused_end), end)] {
+            if start < end {
+                let bytes = unsafe { std::slice::from_raw_parts(start as *const u8, end - start) };
+                writer.write_slice(&[start as u32, bytes.len() as u32]);
+                writer.write_slice(bytes);
+            }
+        }
+    }
 }
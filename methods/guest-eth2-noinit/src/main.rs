@@ -1,7 +1,8 @@
 #![no_main]
 risc0_zkvm::guest::entry!(main);
 
-use risc0_zkvm::guest::env;
+use eth2_journal::{decode_lean_output, Eth2Error, Eth2Journal, Eth2Status, Stage, POST_STATE_FD};
+use risc0_zkvm::guest::env::{self, FdWriter, Write};
 
 extern "C" {
     fn lean_eth2_noinit_entry(
@@ -26,6 +27,17 @@ fn main() {
         );
     }
 
-    let result = unsafe { std::slice::from_raw_parts(output_ptr, output_len) };
-    env::commit_slice(result);
+    let result = if output_ptr.is_null() {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(output_ptr, output_len) }
+    };
+    let (journal, post_state) = decode_lean_output(result).unwrap_or_else(|| {
+        let error = Eth2Error::new(Stage::Runtime, "Lean returned no decodable output");
+        (Eth2Journal::new(Eth2Status::Error(error)), &[][..])
+    });
+    if !post_state.is_empty() {
+        FdWriter::new(POST_STATE_FD, |_| {}).write_slice(post_state);
+    }
+    env::commit(&journal);
 }
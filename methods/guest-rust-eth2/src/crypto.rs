@@ -0,0 +1,141 @@
+//! BLS12-381 signatures and signing domains.
+//! Each signature site in the transition is gated by a bit in the signature-check mask,
+//! so verification can be switched between real and stubbed mode per site.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt};
+
+use risc0_zkvm::sha::{Impl, Sha256};
+
+use crate::ssz::{bytes32_root, bytes_vector_root, merkleize, Chunk, HashTreeRoot};
+
+pub type Domain = Chunk;
+pub type DomainType = [u8; 4];
+
+pub const DOMAIN_BEACON_PROPOSER: DomainType = [0x00, 0, 0, 0];
+pub const DOMAIN_BEACON_ATTESTER: DomainType = [0x01, 0, 0, 0];
+pub const DOMAIN_RANDAO: DomainType = [0x02, 0, 0, 0];
+pub const DOMAIN_DEPOSIT: DomainType = [0x03, 0, 0, 0];
+pub const DOMAIN_VOLUNTARY_EXIT: DomainType = [0x04, 0, 0, 0];
+pub const DOMAIN_SYNC_COMMITTEE: DomainType = [0x07, 0, 0, 0];
+pub const DOMAIN_BLS_TO_EXECUTION_CHANGE: DomainType = [0x0A, 0, 0, 0];
+
+pub const GENESIS_FORK_VERSION: [u8; 4] = [0, 0, 0, 0];
+
+// Proof-of-possession ciphersuite used by the consensus layer
+const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+// ── Signature-check switches ─────────────────────
+
+pub const CHECK_BLOCK: u32 = 1 << 0;
+pub const CHECK_RANDAO: u32 = 1 << 1;
+pub const CHECK_PROPOSER_SLASHING: u32 = 1 << 2;
+pub const CHECK_ATTESTER_SLASHING: u32 = 1 << 3;
+pub const CHECK_ATTESTATION: u32 = 1 << 4;
+pub const CHECK_DEPOSIT: u32 = 1 << 5;
+pub const CHECK_VOLUNTARY_EXIT: u32 = 1 << 6;
+pub const CHECK_SYNC_AGGREGATE: u32 = 1 << 7;
+pub const CHECK_BLS_TO_EXECUTION: u32 = 1 << 8;
+
+// All sites stubbed unless the input enables them
+static SIGNATURE_CHECKS: AtomicU32 = AtomicU32::new(0);
+
+pub fn set_signature_checks(mask: u32) { SIGNATURE_CHECKS.store(mask, Ordering::Relaxed); }
+pub fn check_enabled(site: u32) -> bool { SIGNATURE_CHECKS.load(Ordering::Relaxed) & site != 0 }
+
+// ── Signing domains ──────────────────────────────
+
+// hash_tree_root(ForkData(current_version, genesis_validators_root))
+pub fn compute_fork_data_root(current_version: &[u8], genesis_validators_root: &[u8]) -> Chunk {
+    merkleize(&[bytes_vector_root(current_version, 4), bytes32_root(genesis_validators_root)], 2)
+}
+
+pub fn compute_domain(domain_type: DomainType, fork_version: &[u8], genesis_validators_root: &[u8]) -> Domain {
+    let fork_data_root = compute_fork_data_root(fork_version, genesis_validators_root);
+    let mut domain = [0u8; 32];
+    domain[..4].copy_from_slice(&domain_type);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    domain
+}
+
+// hash_tree_root(SigningData(object_root, domain))
+pub fn compute_signing_root<T: HashTreeRoot>(object: &T, domain: &Domain) -> Chunk {
+    merkleize(&[object.hash_tree_root(), *domain], 2)
+}
+
+// SHA-256 of arbitrary bytes (spec `hash`)
+pub fn hash(data: &[u8]) -> Chunk {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(Impl::hash_bytes(data).as_bytes());
+    out
+}
+
+// ── BLS12-381 ────────────────────────────────────
+
+fn decode_pubkey(pubkey: &[u8]) -> Option<G1Affine> {
+    let bytes: &[u8; 48] = pubkey.try_into().ok()?;
+    let point = Option::<G1Affine>::from(G1Affine::from_compressed(bytes))?;
+    // KeyValidate: reject the identity
+    if bool::from(point.is_identity()) { None } else { Some(point) }
+}
+
+fn decode_signature(signature: &[u8]) -> Option<G2Affine> {
+    let bytes: &[u8; 96] = signature.try_into().ok()?;
+    Option::<G2Affine>::from(G2Affine::from_compressed(bytes))
+}
+
+pub fn hash_to_g2(message: &[u8]) -> G2Projective {
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(message, DST)
+}
+
+// e(pubkey, H(message)) == e(g1, signature)
+fn pairing_check(pubkey: &G1Affine, message: &[u8], signature: &G2Affine) -> bool {
+    let h = G2Affine::from(hash_to_g2(message));
+    let neg_g1 = -G1Affine::generator();
+    multi_miller_loop(&[(pubkey, &G2Prepared::from(h)), (&neg_g1, &G2Prepared::from(*signature))])
+        .final_exponentiation()
+        == Gt::identity()
+}
+
+pub fn bls_verify(pubkey: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    match (decode_pubkey(pubkey), decode_signature(signature)) {
+        (Some(pk), Some(sig)) => pairing_check(&pk, message, &sig),
+        _ => false,
+    }
+}
+
+pub fn fast_aggregate_verify(pubkeys: &[&[u8]], message: &[u8], signature: &[u8]) -> bool {
+    if pubkeys.is_empty() { return false; }
+    let mut aggregate = G1Projective::identity();
+    for pubkey in pubkeys {
+        match decode_pubkey(pubkey) {
+            Some(pk) => aggregate += pk,
+            None => return false,
+        }
+    }
+    match decode_signature(signature) {
+        Some(sig) => pairing_check(&G1Affine::from(aggregate), message, &sig),
+        None => false,
+    }
+}
+
+// Sum of the public keys, each passing KeyValidate; None if any does not
+pub fn eth_aggregate_pubkeys(pubkeys: &[&[u8]]) -> Option<Vec<u8>> {
+    if pubkeys.is_empty() { return None; }
+    let mut aggregate = G1Projective::identity();
+    for pubkey in pubkeys {
+        aggregate += decode_pubkey(pubkey)?;
+    }
+    Some(G1Affine::from(aggregate).to_compressed().to_vec())
+}
+
+// Altair variant: no participants and the point-at-infinity signature is valid
+pub fn eth_fast_aggregate_verify(pubkeys: &[&[u8]], message: &[u8], signature: &[u8]) -> bool {
+    if pubkeys.is_empty() {
+        return decode_signature(signature).is_some_and(|sig| bool::from(sig.is_identity()));
+    }
+    fast_aggregate_verify(pubkeys, message, signature)
+}
+
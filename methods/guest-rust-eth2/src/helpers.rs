@@ -0,0 +1,261 @@
+//! Ethereum Consensus Layer helper functions — Pure Rust.
+//! Mirrors the Lean Eth2.Helpers module.
+
+use crate::crypto::{compute_domain, hash, Domain, DomainType, DOMAIN_BEACON_PROPOSER, DOMAIN_SYNC_COMMITTEE};
+use crate::types::*;
+
+// ── Epoch / Slot conversions ─────────────────────
+
+pub fn compute_epoch_at_slot(slot: Slot) -> Epoch { slot / SLOTS_PER_EPOCH }
+pub fn compute_start_slot_at_epoch(epoch: Epoch) -> Slot { epoch * SLOTS_PER_EPOCH }
+pub fn get_current_epoch(state: &BeaconState) -> Epoch { compute_epoch_at_slot(state.slot) }
+pub fn get_previous_epoch(state: &BeaconState) -> Epoch {
+    let ce = get_current_epoch(state);
+    if ce > 0 { ce - 1 } else { ce }
+}
+
+// ── Validator predicates ─────────────────────────
+
+pub fn is_active_validator(v: &Validator, epoch: Epoch) -> bool {
+    v.activation_epoch <= epoch && epoch < v.exit_epoch
+}
+
+pub fn is_eligible_for_activation_queue(v: &Validator) -> bool {
+    v.activation_eligibility_epoch == FAR_FUTURE_EPOCH && v.effective_balance == MAX_EFFECTIVE_BALANCE
+}
+
+pub fn is_eligible_for_activation(state: &BeaconState, v: &Validator) -> bool {
+    v.activation_eligibility_epoch <= state.finalized_checkpoint.epoch
+        && v.activation_epoch == FAR_FUTURE_EPOCH
+}
+
+pub fn is_slashable_validator(v: &Validator, epoch: Epoch) -> bool {
+    !v.slashed && v.activation_epoch <= epoch && epoch < v.withdrawable_epoch
+}
+
+// ── Active validator queries ─────────────────────
+
+pub fn get_active_validator_indices(state: &BeaconState, epoch: Epoch) -> Vec<usize> {
+    (0..state.validators.len())
+        .filter(|&i| is_active_validator(&state.validators[i], epoch))
+        .collect()
+}
+
+pub fn get_active_validator_count(state: &BeaconState, epoch: Epoch) -> u64 {
+    state.validators.iter().filter(|v| is_active_validator(v, epoch)).count() as u64
+}
+
+// ── Balance helpers ──────────────────────────────
+
+pub fn get_total_balance(state: &BeaconState, indices: &[usize]) -> Gwei {
+    let total: Gwei = indices
+        .iter()
+        .filter(|&&i| i < state.validators.len())
+        .map(|&i| state.validators[i].effective_balance)
+        .sum();
+    total.max(EFFECTIVE_BALANCE_INCREMENT)
+}
+
+pub fn get_total_active_balance(state: &BeaconState) -> Gwei {
+    get_total_balance(state, &get_active_validator_indices(state, get_current_epoch(state)))
+}
+
+pub fn increase_balance(state: &mut BeaconState, index: usize, delta: Gwei) {
+    if index < state.balances.len() {
+        state.balances[index] += delta;
+    }
+}
+
+pub fn decrease_balance(state: &mut BeaconState, index: usize, delta: Gwei) {
+    if index < state.balances.len() {
+        state.balances[index] = state.balances[index].saturating_sub(delta);
+    }
+}
+
+// ── Reward computation ───────────────────────────
+
+pub fn integer_squareroot(n: u64) -> u64 {
+    if n == 0 { return 0; }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x { x = y; y = (x + n / x) / 2; }
+    x
+}
+
+pub fn get_base_reward_per_increment(state: &BeaconState) -> Gwei {
+    let total = get_total_active_balance(state);
+    let sqrt = integer_squareroot(total);
+    (EFFECTIVE_BALANCE_INCREMENT * BASE_REWARD_FACTOR).checked_div(sqrt).unwrap_or(0)
+}
+
+// ── Validator lifecycle ──────────────────────────
+
+pub fn get_validator_churn_limit(state: &BeaconState) -> u64 {
+    let active_count = get_active_validator_count(state, get_current_epoch(state));
+    (active_count / CHURN_LIMIT_QUOTIENT).max(MIN_PER_EPOCH_CHURN_LIMIT)
+}
+
+pub fn initiate_validator_exit(state: &mut BeaconState, index: usize) {
+    if index >= state.validators.len() { return; }
+    // Already initiated
+    if state.validators[index].exit_epoch != FAR_FUTURE_EPOCH { return; }
+    // Find the maximum exit epoch among all validators
+    let current_epoch = get_current_epoch(state);
+    let mut exit_epoch = compute_epoch_at_slot(compute_start_slot_at_epoch(current_epoch));
+    for v in &state.validators {
+        if v.exit_epoch != FAR_FUTURE_EPOCH && v.exit_epoch > exit_epoch {
+            exit_epoch = v.exit_epoch;
+        }
+    }
+    // Bump if at churn limit
+    let churn_limit = get_validator_churn_limit(state);
+    let exit_count = state.validators.iter().filter(|v| v.exit_epoch == exit_epoch).count() as u64;
+    if exit_count >= churn_limit {
+        exit_epoch += 1;
+    }
+    let v = &mut state.validators[index];
+    v.exit_epoch = exit_epoch;
+    v.withdrawable_epoch = exit_epoch + MIN_VALIDATOR_WITHDRAWABILITY_DELAY;
+}
+
+// Slash a validator (mark as slashed, initiate exit, apply penalty)
+pub fn slash_validator(state: &mut BeaconState, slashed_index: usize, whistleblower_index: Option<usize>) {
+    if slashed_index >= state.validators.len() { return; }
+    let epoch = get_current_epoch(state);
+    initiate_validator_exit(state, slashed_index);
+    let v = &mut state.validators[slashed_index];
+    v.slashed = true;
+    v.withdrawable_epoch = v.withdrawable_epoch.max(epoch + EPOCHS_PER_SLASHINGS_VECTOR);
+    let effective_balance = v.effective_balance;
+    // Record slashing in slashings vector
+    let slashings_idx = (epoch % EPOCHS_PER_SLASHINGS_VECTOR) as usize;
+    if slashings_idx < state.slashings.len() {
+        state.slashings[slashings_idx] += effective_balance;
+    }
+    decrease_balance(state, slashed_index, effective_balance / MIN_SLASHING_PENALTY_QUOTIENT_BELLATRIX);
+    // The proposer takes its share of the reward; the whistleblower (the proposer by default) the rest
+    let proposer_index = get_beacon_proposer_index(state);
+    let whistleblower_index = whistleblower_index.unwrap_or(proposer_index);
+    let whistleblower_reward = effective_balance / WHISTLEBLOWER_REWARD_QUOTIENT;
+    let proposer_reward = whistleblower_reward * PROPOSER_WEIGHT / WEIGHT_DENOMINATOR;
+    increase_balance(state, proposer_index, proposer_reward);
+    increase_balance(state, whistleblower_index, whistleblower_reward - proposer_reward);
+}
+
+// ── Participation flag helpers ───────────────────
+
+pub fn has_flag(flags: u8, index: usize) -> bool { (flags >> index) & 1 == 1 }
+pub fn add_flag(flags: u8, index: usize) -> u8 { flags | (1 << index) }
+
+// ── Misc helpers ─────────────────────────────────
+
+pub fn get_randao_mix(state: &BeaconState, epoch: Epoch) -> Bytes32 {
+    let idx = (epoch % EPOCHS_PER_HISTORICAL_VECTOR) as usize;
+    if idx < state.randao_mixes.len() { state.randao_mixes[idx].clone() } else { vec![0u8; 32] }
+}
+
+// Get block root at slot (from circular buffer)
+pub fn get_block_root_at_slot(state: &BeaconState, slot: Slot) -> Root {
+    let idx = (slot % SLOTS_PER_HISTORICAL_ROOT) as usize;
+    if idx < state.block_roots.len() { state.block_roots[idx].clone() } else { vec![0u8; 32] }
+}
+
+// Get block root at epoch (returns root at start slot of epoch)
+pub fn get_block_root(state: &BeaconState, epoch: Epoch) -> Root {
+    get_block_root_at_slot(state, compute_start_slot_at_epoch(epoch))
+}
+
+// ── Shuffling / proposer selection ───────────────
+
+pub fn get_seed(state: &BeaconState, epoch: Epoch, domain_type: DomainType) -> Bytes32 {
+    let mix = get_randao_mix(state, epoch + EPOCHS_PER_HISTORICAL_VECTOR - MIN_SEED_LOOKAHEAD - 1);
+    let mut preimage = domain_type.to_vec();
+    preimage.extend_from_slice(&epoch.to_le_bytes());
+    preimage.extend_from_slice(&mix);
+    hash(&preimage).to_vec()
+}
+
+// Swap-or-not shuffle: position of `index` in a permutation of 0..index_count
+pub fn compute_shuffled_index(mut index: u64, index_count: u64, seed: &[u8]) -> u64 {
+    if index_count == 0 { return index; }
+    for round in 0..SHUFFLE_ROUND_COUNT {
+        let mut input = seed.to_vec();
+        input.push(round);
+        let pivot = u64::from_le_bytes(hash(&input)[..8].try_into().unwrap()) % index_count;
+        let flip = (pivot + index_count - index) % index_count;
+        let position = index.max(flip);
+        input.extend_from_slice(&((position / 256) as u32).to_le_bytes());
+        let source = hash(&input);
+        let byte = source[((position % 256) / 8) as usize];
+        if (byte >> (position % 8)) & 1 == 1 { index = flip; }
+    }
+    index
+}
+
+// Sample shuffled candidates, accepting each with probability effective_balance / MAX_EFFECTIVE_BALANCE
+pub fn compute_proposer_index(state: &BeaconState, indices: &[usize], seed: &[u8]) -> usize {
+    const MAX_RANDOM_BYTE: u64 = 255;
+    if indices.is_empty() { return 0; }
+    let total = indices.len() as u64;
+    let mut i: u64 = 0;
+    loop {
+        let candidate = indices[compute_shuffled_index(i % total, total, seed) as usize];
+        let mut input = seed.to_vec();
+        input.extend_from_slice(&(i / 32).to_le_bytes());
+        let random_byte = hash(&input)[(i % 32) as usize] as u64;
+        if state.validators[candidate].effective_balance * MAX_RANDOM_BYTE >= MAX_EFFECTIVE_BALANCE * random_byte {
+            return candidate;
+        }
+        i += 1;
+    }
+}
+
+pub fn get_beacon_proposer_index(state: &BeaconState) -> usize {
+    let epoch = get_current_epoch(state);
+    let mut preimage = get_seed(state, epoch, DOMAIN_BEACON_PROPOSER);
+    preimage.extend_from_slice(&state.slot.to_le_bytes());
+    compute_proposer_index(state, &get_active_validator_indices(state, epoch), &hash(&preimage))
+}
+
+// Sample SYNC_COMMITTEE_SIZE members (with repeats) from the validators active next epoch,
+// weighted by effective balance like compute_proposer_index; empty if none are active
+pub fn get_next_sync_committee_indices(state: &BeaconState) -> Vec<usize> {
+    const MAX_RANDOM_BYTE: u64 = 255;
+    let epoch = get_current_epoch(state) + 1;
+    let active = get_active_validator_indices(state, epoch);
+    if active.is_empty() { return Vec::new(); }
+    let total = active.len() as u64;
+    let seed = get_seed(state, epoch, DOMAIN_SYNC_COMMITTEE);
+    let mut indices = Vec::with_capacity(SYNC_COMMITTEE_SIZE);
+    let mut i: u64 = 0;
+    while indices.len() < SYNC_COMMITTEE_SIZE {
+        let candidate = active[compute_shuffled_index(i % total, total, &seed) as usize];
+        let mut input = seed.clone();
+        input.extend_from_slice(&(i / 32).to_le_bytes());
+        let random_byte = hash(&input)[(i % 32) as usize] as u64;
+        if state.validators[candidate].effective_balance * MAX_RANDOM_BYTE >= MAX_EFFECTIVE_BALANCE * random_byte {
+            indices.push(candidate);
+        }
+        i += 1;
+    }
+    indices
+}
+
+// Signing domain at `epoch`, using the fork version active at that epoch
+pub fn get_domain(state: &BeaconState, domain_type: DomainType, epoch: Epoch) -> Domain {
+    let fork_version = if epoch < state.fork.epoch { &state.fork.previous_version } else { &state.fork.current_version };
+    compute_domain(domain_type, fork_version, &state.genesis_validators_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shuffled_index() {
+        let seed: Vec<u8> = (0..32).collect();
+        let permutation: Vec<u64> = (0..10).map(|i| compute_shuffled_index(i, 10, &seed)).collect();
+        assert_eq!(permutation, [5, 2, 3, 1, 9, 6, 7, 4, 0, 8]);
+        assert_eq!(compute_shuffled_index(123, 1000, &seed), 357);
+    }
+}
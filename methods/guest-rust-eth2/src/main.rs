@@ -1,26 +1,69 @@
 #![no_main]
 risc0_zkvm::guest::entry!(main);
 
-use risc0_zkvm::guest::env;
+use eth2_journal::{Eth2Error, Eth2Journal, Eth2Status, Stage, POST_STATE_FD};
+use risc0_zkvm::guest::env::{self, FdWriter, Write};
+
+use ssz::HashTreeRoot;
+use types::{BeaconState, SignedBeaconBlock};
 
 mod types;
+mod crypto;
+mod helpers;
+mod ssz;
 mod transition;
 
 fn main() {
     let input: Vec<u8> = env::read();
-    let result = match types::BeaconState::deserialize(&input) {
-        Some((pre_state, offset)) => {
-            match types::SignedBeaconBlock::deserialize(&input[offset..]) {
-                Some((signed_block, _)) => {
-                    match transition::state_transition(pre_state, &signed_block) {
-                        Ok(post_state) => post_state.serialize(),
-                        Err(_) => vec![0xFD], // STF error
-                    }
-                }
-                None => vec![0xFE], // Block decode error
-            }
+    let (journal, post_state) = run(&input);
+    if let Some(post_state) = post_state {
+        FdWriter::new(POST_STATE_FD, |_| {}).write_slice(&post_state.serialize());
+    }
+    env::commit(&journal);
+}
+
+// Decode, apply every block in order and fill in the journal; the post-state is returned only if requested
+fn run(input: &[u8]) -> (Eth2Journal, Option<BeaconState>) {
+    let mut journal = Eth2Journal::new(decode_error(Stage::StateDecode));
+    let Some((pre_state, offset)) = BeaconState::deserialize(input) else { return (journal, None) };
+    journal.pre_state_root = pre_state.hash_tree_root();
+
+    journal.status = decode_error(Stage::BlockDecode);
+    let Some((blocks, blocks_len)) = SignedBeaconBlock::deserialize_list(&input[offset..]) else { return (journal, None) };
+    if blocks.is_empty() { return (journal, None) }
+
+    // Trailing options: validate_result flag, u32 signature-check mask, emit_post_state flag.
+    // Missing options skip the state root check, stub every signature and keep the post-state private.
+    let options = &input[offset + blocks_len..];
+    let validate_result = options.first().is_some_and(|&b| b != 0);
+    let signature_checks = options.get(1..5).map_or(0, |b| u32::from_le_bytes(b.try_into().unwrap()));
+    let emit_post_state = options.get(5).is_some_and(|&b| b != 0);
+    crypto::set_signature_checks(signature_checks);
+
+    let result = blocks.iter().try_fold(pre_state, |state, signed_block| {
+        let state = transition::state_transition(state, signed_block, validate_result)?;
+        journal.blocks_applied += 1;
+        Ok(state)
+    });
+    // Last block on success, the failing one otherwise
+    let block = &blocks[(journal.blocks_applied as usize).min(blocks.len() - 1)].message;
+    journal.block_root = block.hash_tree_root();
+    journal.slot = block.slot;
+
+    match result {
+        Ok(post_state) => {
+            journal.post_state_root = post_state.hash_tree_root();
+            journal.status = Eth2Status::Ok;
+            (journal, emit_post_state.then_some(post_state))
+        }
+        Err(error) => {
+            journal.status = Eth2Status::Error(error);
+            (journal, None)
         }
-        None => vec![0xFF], // State decode error
-    };
-    env::commit_slice(&result);
+    }
+}
+
+// Decoders carry no reason; the stage says which input was malformed
+fn decode_error(stage: Stage) -> Eth2Status {
+    Eth2Status::Error(Eth2Error::new(stage, ""))
 }
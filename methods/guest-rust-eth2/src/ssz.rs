@@ -0,0 +1,648 @@
+//! SSZ hash_tree_root for the consensus containers in types.rs.
+//! Hashing goes through risc0_zkvm::sha so it uses the SHA-256 accelerator.
+
+use std::sync::{Mutex, OnceLock};
+
+use risc0_zkvm::sha::{Impl, Sha256};
+
+use crate::types::*;
+
+pub type Chunk = [u8; 32];
+
+// ── SSZ list / vector limits (mainnet preset) ────
+
+const HISTORICAL_ROOTS_LIMIT: u64 = 1 << 24;
+const VALIDATOR_REGISTRY_LIMIT: u64 = 1 << 40;
+const MAX_VALIDATORS_PER_COMMITTEE: u64 = 2048;
+const MAX_PROPOSER_SLASHINGS: u64 = 16;
+const MAX_ATTESTER_SLASHINGS: u64 = 2;
+const MAX_ATTESTATIONS: u64 = 128;
+const MAX_DEPOSITS: u64 = 16;
+const MAX_VOLUNTARY_EXITS: u64 = 16;
+const MAX_BLS_TO_EXECUTION_CHANGES: u64 = 16;
+const DEPOSIT_PROOF_LENGTH: u64 = 33;
+const BYTES_PER_LOGS_BLOOM: usize = 256;
+const MAX_EXTRA_DATA_BYTES: u64 = 32;
+const MAX_BYTES_PER_TRANSACTION: u64 = 1 << 30;
+const MAX_TRANSACTIONS_PER_PAYLOAD: u64 = 1 << 20;
+const JUSTIFICATION_BITS_LENGTH: u64 = 4;
+
+// Deep enough for the largest limit above (2^40 validators)
+const MAX_DEPTH: usize = 48;
+
+// ── Merkleization primitives ─────────────────────
+
+fn hash_pair(a: &Chunk, b: &Chunk) -> Chunk {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(a);
+    buf[32..].copy_from_slice(b);
+    let mut out = [0u8; 32];
+    out.copy_from_slice(Impl::hash_bytes(&buf).as_bytes());
+    out
+}
+
+// zero_hash(d) = root of a depth-d tree of zero chunks
+fn zero_hash(depth: usize) -> Chunk {
+    static ZERO_HASHES: OnceLock<Vec<Chunk>> = OnceLock::new();
+    let table = ZERO_HASHES.get_or_init(|| {
+        let mut t = vec![[0u8; 32]; MAX_DEPTH + 1];
+        for d in 1..=MAX_DEPTH {
+            t[d] = hash_pair(&t[d - 1], &t[d - 1]);
+        }
+        t
+    });
+    table[depth]
+}
+
+fn ceil_log2(n: u64) -> usize {
+    if n <= 1 { 0 } else { (64 - (n - 1).leading_zeros()) as usize }
+}
+
+// Merkleize chunks into a tree sized for `limit` chunks, padding with zero subtrees.
+// Over-long input widens the tree instead of panicking; such objects are invalid anyway.
+pub fn merkleize(chunks: &[Chunk], limit: u64) -> Chunk {
+    let depth = ceil_log2(limit.max(chunks.len() as u64));
+    if chunks.is_empty() { return zero_hash(depth); }
+    let mut layer = chunks.to_vec();
+    for d in 0..depth {
+        if layer.len() % 2 == 1 { layer.push(zero_hash(d)); }
+        layer = layer.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+    }
+    layer[0]
+}
+
+/// Merkle tree kept between calls, with the same root as `merkleize`. Leaves equal to the
+/// previous call's keep their cached branch, so only the paths above changed leaves are
+/// rehashed; any other shape rebuilds the tree.
+struct CachedTree {
+    depth: usize,
+    // layers[0] holds the leaves, layers[d] the nodes at height d left of the zero padding
+    layers: Vec<Vec<Chunk>>,
+}
+
+impl CachedTree {
+    const fn new() -> Self {
+        CachedTree { depth: 0, layers: Vec::new() }
+    }
+
+    fn root(&mut self, chunks: &[Chunk], limit: u64) -> Chunk {
+        let depth = ceil_log2(limit.max(chunks.len() as u64));
+        if chunks.is_empty() { return zero_hash(depth); }
+        if self.depth != depth || self.layers.first().is_none_or(|leaves| leaves.len() != chunks.len()) {
+            self.depth = depth;
+            self.layers = vec![chunks.to_vec()];
+            for d in 0..depth {
+                let below = &self.layers[d];
+                let layer = (0..below.len().div_ceil(2)).map(|i| parent(below, i, d)).collect();
+                self.layers.push(layer);
+            }
+        } else {
+            let mut dirty: Vec<usize> = (0..chunks.len()).filter(|&i| self.layers[0][i] != chunks[i]).collect();
+            for &i in &dirty { self.layers[0][i] = chunks[i]; }
+            for d in 0..depth {
+                dirty = dirty.iter().map(|i| i / 2).collect();
+                dirty.dedup();
+                for &i in &dirty {
+                    let node = parent(&self.layers[d], i, d);
+                    self.layers[d + 1][i] = node;
+                }
+            }
+        }
+        self.layers[depth][0]
+    }
+}
+
+// Node `i` of the layer above `layer` (at height d); a missing right child is zero padding
+fn parent(layer: &[Chunk], i: usize, d: usize) -> Chunk {
+    let right = layer.get(2 * i + 1).copied().unwrap_or_else(|| zero_hash(d));
+    hash_pair(&layer[2 * i], &right)
+}
+
+pub fn mix_in_length(root: &Chunk, length: u64) -> Chunk {
+    let mut len_chunk = [0u8; 32];
+    len_chunk[..8].copy_from_slice(&length.to_le_bytes());
+    hash_pair(root, &len_chunk)
+}
+
+fn pack(bytes: &[u8]) -> Vec<Chunk> {
+    bytes
+        .chunks(32)
+        .map(|c| {
+            let mut chunk = [0u8; 32];
+            chunk[..c.len()].copy_from_slice(c);
+            chunk
+        })
+        .collect()
+}
+
+fn chunk_count(byte_len: u64) -> u64 { byte_len.div_ceil(32) }
+
+// ── Basic types ──────────────────────────────────
+
+fn u64_root(v: u64) -> Chunk {
+    let mut chunk = [0u8; 32];
+    chunk[..8].copy_from_slice(&v.to_le_bytes());
+    chunk
+}
+
+fn bool_root(v: bool) -> Chunk {
+    let mut chunk = [0u8; 32];
+    chunk[0] = v as u8;
+    chunk
+}
+
+// ByteVector[n]; the Vec<u8> fields are zero-padded / truncated to their spec size
+pub fn bytes_vector_root(bytes: &[u8], n: usize) -> Chunk {
+    let mut fixed = vec![0u8; n];
+    let len = bytes.len().min(n);
+    fixed[..len].copy_from_slice(&bytes[..len]);
+    merkleize(&pack(&fixed), chunk_count(n as u64))
+}
+
+pub fn bytes32_root(bytes: &[u8]) -> Chunk { bytes_vector_root(bytes, 32) }
+
+fn byte_list_root(bytes: &[u8], max_len: u64) -> Chunk {
+    mix_in_length(&merkleize(&pack(bytes), chunk_count(max_len)), bytes.len() as u64)
+}
+
+fn u64_list_root(values: &[u64], max_len: u64) -> Chunk {
+    mix_in_length(&u64_vector_root(values, max_len), values.len() as u64)
+}
+
+fn u64_vector_root(values: &[u64], n: u64) -> Chunk {
+    let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    merkleize(&pack(&bytes), chunk_count(n * 8))
+}
+
+// Vector[Bytes32, n]; missing trailing entries hash as zero roots
+fn roots_vector_root(roots: &[Root], n: u64) -> Chunk {
+    let chunks: Vec<Chunk> = roots.iter().map(|r| bytes32_root(r)).collect();
+    merkleize(&chunks, n)
+}
+
+// roots_vector_root for a state vector re-hashed every slot, through that field's tree
+fn cached_roots_vector_root(tree: &Mutex<CachedTree>, roots: &[Root], n: u64) -> Chunk {
+    let chunks: Vec<Chunk> = roots.iter().map(|r| bytes32_root(r)).collect();
+    tree.lock().unwrap().root(&chunks, n)
+}
+
+fn roots_list_root(roots: &[Root], max_len: u64) -> Chunk {
+    mix_in_length(&roots_vector_root(roots, max_len), roots.len() as u64)
+}
+
+fn list_root<T: HashTreeRoot>(items: &[T], max_len: u64) -> Chunk {
+    let chunks: Vec<Chunk> = items.iter().map(|i| i.hash_tree_root()).collect();
+    mix_in_length(&merkleize(&chunks, max_len), items.len() as u64)
+}
+
+fn bitvector_root(bits: &[u8], n: u64) -> Chunk {
+    bytes_vector_root(bits, n.div_ceil(8) as usize)
+}
+
+// Bitlist without a delimiter bit: every stored byte counts as 8 bits
+fn bitlist_root(bits: &[u8], max_len: u64) -> Chunk {
+    mix_in_length(&merkleize(&pack(bits), max_len.div_ceil(256)), bits.len() as u64 * 8)
+}
+
+fn pubkey_root(pubkey: &[u8]) -> Chunk { bytes_vector_root(pubkey, 48) }
+fn signature_root(signature: &[u8]) -> Chunk { bytes_vector_root(signature, 96) }
+
+fn container_root(fields: &[Chunk]) -> Chunk { merkleize(fields, fields.len() as u64) }
+
+// ── Containers ───────────────────────────────────
+
+pub trait HashTreeRoot {
+    fn hash_tree_root(&self) -> Chunk;
+}
+
+// hash_tree_root as a Root, for storing into state fields
+pub fn hash_tree_root<T: HashTreeRoot>(value: &T) -> Root { value.hash_tree_root().to_vec() }
+
+impl HashTreeRoot for u64 {
+    fn hash_tree_root(&self) -> Chunk { u64_root(*self) }
+}
+
+// A Bytes32 / Root is its own hash_tree_root
+impl HashTreeRoot for Chunk {
+    fn hash_tree_root(&self) -> Chunk { *self }
+}
+
+impl HashTreeRoot for Fork {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            bytes_vector_root(&self.previous_version, 4),
+            bytes_vector_root(&self.current_version, 4),
+            u64_root(self.epoch),
+        ])
+    }
+}
+
+impl HashTreeRoot for Checkpoint {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[u64_root(self.epoch), bytes32_root(&self.root)])
+    }
+}
+
+impl HashTreeRoot for Validator {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            pubkey_root(&self.pubkey),
+            bytes32_root(&self.withdrawal_credentials),
+            u64_root(self.effective_balance),
+            bool_root(self.slashed),
+            u64_root(self.activation_eligibility_epoch),
+            u64_root(self.activation_epoch),
+            u64_root(self.exit_epoch),
+            u64_root(self.withdrawable_epoch),
+        ])
+    }
+}
+
+impl HashTreeRoot for Eth1Data {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            bytes32_root(&self.deposit_root),
+            u64_root(self.deposit_count),
+            bytes32_root(&self.block_hash),
+        ])
+    }
+}
+
+impl HashTreeRoot for BeaconBlockHeader {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            u64_root(self.slot),
+            u64_root(self.proposer_index),
+            bytes32_root(&self.parent_root),
+            bytes32_root(&self.state_root),
+            bytes32_root(&self.body_root),
+        ])
+    }
+}
+
+impl HashTreeRoot for SyncCommittee {
+    fn hash_tree_root(&self) -> Chunk {
+        let pubkeys: Vec<Chunk> = self.pubkeys.iter().map(|p| pubkey_root(p)).collect();
+        container_root(&[
+            merkleize(&pubkeys, SYNC_COMMITTEE_SIZE as u64),
+            pubkey_root(&self.aggregate_pubkey),
+        ])
+    }
+}
+
+impl HashTreeRoot for SyncAggregate {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            bitvector_root(&self.sync_committee_bits, SYNC_COMMITTEE_SIZE as u64),
+            signature_root(&self.sync_committee_signature),
+        ])
+    }
+}
+
+impl HashTreeRoot for ExecutionPayloadHeader {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            bytes32_root(&self.parent_hash),
+            bytes_vector_root(&self.fee_recipient, 20),
+            bytes32_root(&self.state_root),
+            bytes32_root(&self.receipts_root),
+            bytes_vector_root(&self.logs_bloom, BYTES_PER_LOGS_BLOOM),
+            bytes32_root(&self.prev_randao),
+            u64_root(self.block_number),
+            u64_root(self.gas_limit),
+            u64_root(self.gas_used),
+            u64_root(self.timestamp),
+            byte_list_root(&self.extra_data, MAX_EXTRA_DATA_BYTES),
+            u64_root(self.base_fee_per_gas),
+            bytes32_root(&self.block_hash),
+            bytes32_root(&self.transactions_root),
+            bytes32_root(&self.withdrawals_root),
+        ])
+    }
+}
+
+impl HashTreeRoot for Withdrawal {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            u64_root(self.index),
+            u64_root(self.validator_index),
+            bytes_vector_root(&self.address, 20),
+            u64_root(self.amount),
+        ])
+    }
+}
+
+impl ExecutionPayload {
+    pub fn transactions_root(&self) -> Chunk {
+        let txs: Vec<Chunk> = self
+            .transactions
+            .iter()
+            .map(|tx| byte_list_root(tx, MAX_BYTES_PER_TRANSACTION))
+            .collect();
+        mix_in_length(&merkleize(&txs, MAX_TRANSACTIONS_PER_PAYLOAD), txs.len() as u64)
+    }
+
+    pub fn withdrawals_root(&self) -> Chunk {
+        list_root(&self.withdrawals, MAX_WITHDRAWALS_PER_PAYLOAD as u64)
+    }
+}
+
+impl HashTreeRoot for ExecutionPayload {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            bytes32_root(&self.parent_hash),
+            bytes_vector_root(&self.fee_recipient, 20),
+            bytes32_root(&self.state_root),
+            bytes32_root(&self.receipts_root),
+            bytes_vector_root(&self.logs_bloom, BYTES_PER_LOGS_BLOOM),
+            bytes32_root(&self.prev_randao),
+            u64_root(self.block_number),
+            u64_root(self.gas_limit),
+            u64_root(self.gas_used),
+            u64_root(self.timestamp),
+            byte_list_root(&self.extra_data, MAX_EXTRA_DATA_BYTES),
+            u64_root(self.base_fee_per_gas),
+            bytes32_root(&self.block_hash),
+            self.transactions_root(),
+            self.withdrawals_root(),
+        ])
+    }
+}
+
+impl HashTreeRoot for HistoricalSummary {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            bytes32_root(&self.block_summary_root),
+            bytes32_root(&self.state_summary_root),
+        ])
+    }
+}
+
+impl HashTreeRoot for SignedBeaconBlockHeader {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[self.message.hash_tree_root(), signature_root(&self.signature)])
+    }
+}
+
+impl HashTreeRoot for AttestationData {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            u64_root(self.slot),
+            u64_root(self.index),
+            bytes32_root(&self.beacon_block_root),
+            self.source.hash_tree_root(),
+            self.target.hash_tree_root(),
+        ])
+    }
+}
+
+impl HashTreeRoot for IndexedAttestation {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            u64_list_root(&self.attesting_indices, MAX_VALIDATORS_PER_COMMITTEE),
+            self.data.hash_tree_root(),
+            signature_root(&self.signature),
+        ])
+    }
+}
+
+impl HashTreeRoot for ProposerSlashing {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[self.signed_header_1.hash_tree_root(), self.signed_header_2.hash_tree_root()])
+    }
+}
+
+impl HashTreeRoot for AttesterSlashing {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[self.attestation_1.hash_tree_root(), self.attestation_2.hash_tree_root()])
+    }
+}
+
+impl HashTreeRoot for Attestation {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            bitlist_root(&self.aggregation_bits, MAX_VALIDATORS_PER_COMMITTEE),
+            self.data.hash_tree_root(),
+            signature_root(&self.signature),
+        ])
+    }
+}
+
+impl HashTreeRoot for DepositData {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            pubkey_root(&self.pubkey),
+            bytes32_root(&self.withdrawal_credentials),
+            u64_root(self.amount),
+            signature_root(&self.signature),
+        ])
+    }
+}
+
+impl DepositData {
+    // hash_tree_root(DepositMessage(pubkey, withdrawal_credentials, amount))
+    pub fn message_root(&self) -> Chunk {
+        container_root(&[
+            pubkey_root(&self.pubkey),
+            bytes32_root(&self.withdrawal_credentials),
+            u64_root(self.amount),
+        ])
+    }
+}
+
+impl HashTreeRoot for Deposit {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[roots_vector_root(&self.proof, DEPOSIT_PROOF_LENGTH), self.data.hash_tree_root()])
+    }
+}
+
+impl HashTreeRoot for VoluntaryExit {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[u64_root(self.epoch), u64_root(self.validator_index)])
+    }
+}
+
+impl HashTreeRoot for SignedVoluntaryExit {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[self.message.hash_tree_root(), signature_root(&self.signature)])
+    }
+}
+
+impl HashTreeRoot for BLSToExecutionChange {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            u64_root(self.validator_index),
+            pubkey_root(&self.from_bls_pubkey),
+            bytes_vector_root(&self.to_execution_address, 20),
+        ])
+    }
+}
+
+impl HashTreeRoot for SignedBLSToExecutionChange {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[self.message.hash_tree_root(), signature_root(&self.signature)])
+    }
+}
+
+impl HashTreeRoot for BeaconBlockBody {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            signature_root(&self.randao_reveal),
+            self.eth1_data.hash_tree_root(),
+            bytes32_root(&self.graffiti),
+            list_root(&self.proposer_slashings, MAX_PROPOSER_SLASHINGS),
+            list_root(&self.attester_slashings, MAX_ATTESTER_SLASHINGS),
+            list_root(&self.attestations, MAX_ATTESTATIONS),
+            list_root(&self.deposits, MAX_DEPOSITS),
+            list_root(&self.voluntary_exits, MAX_VOLUNTARY_EXITS),
+            self.sync_aggregate.hash_tree_root(),
+            self.execution_payload.hash_tree_root(),
+            list_root(&self.bls_to_execution_changes, MAX_BLS_TO_EXECUTION_CHANGES),
+        ])
+    }
+}
+
+impl HashTreeRoot for BeaconBlock {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            u64_root(self.slot),
+            u64_root(self.proposer_index),
+            bytes32_root(&self.parent_root),
+            bytes32_root(&self.state_root),
+            self.body.hash_tree_root(),
+        ])
+    }
+}
+
+impl HashTreeRoot for SignedBeaconBlock {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[self.message.hash_tree_root(), signature_root(&self.signature)])
+    }
+}
+
+// process_slot takes hash_tree_root(state) every slot, and these vectors (8192, 8192 and
+// 65536 entries in a full state) change in one entry per slot
+static BLOCK_ROOTS_TREE: Mutex<CachedTree> = Mutex::new(CachedTree::new());
+static STATE_ROOTS_TREE: Mutex<CachedTree> = Mutex::new(CachedTree::new());
+static RANDAO_MIXES_TREE: Mutex<CachedTree> = Mutex::new(CachedTree::new());
+
+impl BeaconState {
+    pub fn block_roots_root(&self) -> Chunk {
+        cached_roots_vector_root(&BLOCK_ROOTS_TREE, &self.block_roots, SLOTS_PER_HISTORICAL_ROOT)
+    }
+    pub fn state_roots_root(&self) -> Chunk {
+        cached_roots_vector_root(&STATE_ROOTS_TREE, &self.state_roots, SLOTS_PER_HISTORICAL_ROOT)
+    }
+    fn randao_mixes_root(&self) -> Chunk {
+        cached_roots_vector_root(&RANDAO_MIXES_TREE, &self.randao_mixes, EPOCHS_PER_HISTORICAL_VECTOR)
+    }
+}
+
+impl HashTreeRoot for BeaconState {
+    fn hash_tree_root(&self) -> Chunk {
+        container_root(&[
+            u64_root(self.genesis_time),
+            bytes32_root(&self.genesis_validators_root),
+            u64_root(self.slot),
+            self.fork.hash_tree_root(),
+            self.latest_block_header.hash_tree_root(),
+            self.block_roots_root(),
+            self.state_roots_root(),
+            roots_list_root(&self.historical_roots, HISTORICAL_ROOTS_LIMIT),
+            self.eth1_data.hash_tree_root(),
+            list_root(&self.eth1_data_votes, EPOCHS_PER_ETH1_VOTING_PERIOD * SLOTS_PER_EPOCH),
+            u64_root(self.eth1_deposit_index),
+            list_root(&self.validators, VALIDATOR_REGISTRY_LIMIT),
+            u64_list_root(&self.balances, VALIDATOR_REGISTRY_LIMIT),
+            self.randao_mixes_root(),
+            u64_vector_root(&self.slashings, EPOCHS_PER_SLASHINGS_VECTOR),
+            byte_list_root(&self.previous_epoch_participation, VALIDATOR_REGISTRY_LIMIT),
+            byte_list_root(&self.current_epoch_participation, VALIDATOR_REGISTRY_LIMIT),
+            bitvector_root(&self.justification_bits, JUSTIFICATION_BITS_LENGTH),
+            self.previous_justified_checkpoint.hash_tree_root(),
+            self.current_justified_checkpoint.hash_tree_root(),
+            self.finalized_checkpoint.hash_tree_root(),
+            u64_list_root(&self.inactivity_scores, VALIDATOR_REGISTRY_LIMIT),
+            self.current_sync_committee.hash_tree_root(),
+            self.next_sync_committee.hash_tree_root(),
+            self.latest_execution_payload_header.hash_tree_root(),
+            u64_root(self.next_withdrawal_index),
+            u64_root(self.next_withdrawal_validator_index),
+            list_root(&self.historical_summaries, HISTORICAL_ROOTS_LIMIT),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(hex: &str) -> Chunk {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn zero_hashes() {
+        assert_eq!(zero_hash(0), [0u8; 32]);
+        assert_eq!(zero_hash(1), chunk("f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b"));
+        assert_eq!(zero_hash(2), chunk("db56114e00fdd4c1f85c892bf35ac9a89289aaecb1ebd0a96cde606a748b5d71"));
+        assert_eq!(zero_hash(3), chunk("c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c"));
+    }
+
+    #[test]
+    fn checkpoint_root() {
+        let zero = Checkpoint { epoch: 0, root: vec![0; 32] };
+        assert_eq!(zero.hash_tree_root(), zero_hash(1));
+        let checkpoint = Checkpoint { epoch: 3, root: vec![0x11; 32] };
+        assert_eq!(
+            checkpoint.hash_tree_root(),
+            chunk("8d7ec135ffb397a99e8b3794c3adf61271572d368226dc807636996c30776aa6")
+        );
+    }
+
+    #[test]
+    fn cached_tree_matches_merkleize() {
+        let mut tree = CachedTree::new();
+        let mut chunks: Vec<Chunk> = (0..200u8).map(|i| [i; 32]).collect();
+        assert_eq!(tree.root(&chunks, 8192), merkleize(&chunks, 8192));
+        // Changed leaves, including the first and the last
+        for i in [0, 37, 38, 199] {
+            chunks[i] = [0xff; 32];
+            assert_eq!(tree.root(&chunks, 8192), merkleize(&chunks, 8192));
+        }
+        // Other shapes rebuild the tree
+        chunks.push([1; 32]);
+        assert_eq!(tree.root(&chunks, 8192), merkleize(&chunks, 8192));
+        assert_eq!(tree.root(&chunks, 256), merkleize(&chunks, 256));
+        assert_eq!(tree.root(&[], 256), merkleize(&[], 256));
+        assert_eq!(tree.root(&chunks[..1], 1), chunks[0]);
+    }
+
+    #[test]
+    fn block_header_root() {
+        // Five fields, padded to eight leaves
+        let zero = BeaconBlockHeader {
+            slot: 0,
+            proposer_index: 0,
+            parent_root: vec![0; 32],
+            state_root: vec![0; 32],
+            body_root: vec![0; 32],
+        };
+        assert_eq!(zero.hash_tree_root(), zero_hash(3));
+        let header = BeaconBlockHeader {
+            slot: 1,
+            proposer_index: 2,
+            parent_root: vec![0xaa; 32],
+            state_root: vec![0xbb; 32],
+            body_root: vec![0xcc; 32],
+        };
+        assert_eq!(
+            header.hash_tree_root(),
+            chunk("74fb3665753e868ae6d551dec99230ed49292baccab590bf653b78c7dc152bb3")
+        );
+    }
+}
@@ -1,10 +1,11 @@
-/// Ethereum Consensus Layer types — Pure Rust equivalent of the Lean types.
-/// Mirrors the Lean Eth2.Types and Eth2.Containers modules.
+//! Ethereum Consensus Layer types — Pure Rust equivalent of the Lean types.
+//! Mirrors the Lean Eth2.Types and Eth2.Containers modules.
 
 pub type Slot = u64;
 pub type Epoch = u64;
 pub type Gwei = u64;
 pub type ValidatorIndex = u64;
+pub type CommitteeIndex = u64;
 pub type WithdrawalIndex = u64;
 pub type Root = Vec<u8>;
 pub type Bytes32 = Vec<u8>;
@@ -16,6 +17,8 @@ pub const FAR_FUTURE_EPOCH: Epoch = u64::MAX;
 pub const SLOTS_PER_EPOCH: u64 = 32;
 pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
 pub const EPOCHS_PER_HISTORICAL_VECTOR: u64 = 65536;
+pub const MIN_SEED_LOOKAHEAD: u64 = 1;
+pub const SHUFFLE_ROUND_COUNT: u8 = 90;
 pub const EPOCHS_PER_SLASHINGS_VECTOR: u64 = 8192;
 pub const EPOCHS_PER_ETH1_VOTING_PERIOD: u64 = 64;
 pub const EPOCHS_PER_SYNC_COMMITTEE_PERIOD: u64 = 256;
@@ -47,6 +50,8 @@ pub const EJECTION_BALANCE: Gwei = 16_000_000_000;
 pub const SHARD_COMMITTEE_PERIOD: u64 = 256;
 pub const SYNC_COMMITTEE_SIZE: usize = 512;
 pub const MIN_ATTESTATION_INCLUSION_DELAY: u64 = 1;
+pub const BLS_WITHDRAWAL_PREFIX: u8 = 0x00;
+pub const ETH1_ADDRESS_WITHDRAWAL_PREFIX: u8 = 0x01;
 
 #[derive(Clone, Default)]
 pub struct Fork {
@@ -153,13 +158,99 @@ pub struct HistoricalSummary {
     pub state_summary_root: Root,
 }
 
+#[derive(Clone, Default)]
+pub struct SignedBeaconBlockHeader {
+    pub message: BeaconBlockHeader,
+    pub signature: BLSSignature,
+}
+
+#[derive(Clone, Default)]
+pub struct AttestationData {
+    pub slot: Slot,
+    pub index: CommitteeIndex,
+    pub beacon_block_root: Root,
+    pub source: Checkpoint,
+    pub target: Checkpoint,
+}
+
+#[derive(Clone, Default)]
+pub struct IndexedAttestation {
+    pub attesting_indices: Vec<ValidatorIndex>,
+    pub data: AttestationData,
+    pub signature: BLSSignature,
+}
+
+#[derive(Clone, Default)]
+pub struct ProposerSlashing {
+    pub signed_header_1: SignedBeaconBlockHeader,
+    pub signed_header_2: SignedBeaconBlockHeader,
+}
+
+#[derive(Clone, Default)]
+pub struct AttesterSlashing {
+    pub attestation_1: IndexedAttestation,
+    pub attestation_2: IndexedAttestation,
+}
+
+#[derive(Clone, Default)]
+pub struct Attestation {
+    pub aggregation_bits: Vec<u8>,
+    pub data: AttestationData,
+    pub signature: BLSSignature,
+}
+
+#[derive(Clone, Default)]
+pub struct DepositData {
+    pub pubkey: BLSPubkey,
+    pub withdrawal_credentials: Bytes32,
+    pub amount: Gwei,
+    pub signature: BLSSignature,
+}
+
+#[derive(Clone, Default)]
+pub struct Deposit {
+    pub proof: Vec<Bytes32>,
+    pub data: DepositData,
+}
+
+#[derive(Clone, Default)]
+pub struct VoluntaryExit {
+    pub epoch: Epoch,
+    pub validator_index: ValidatorIndex,
+}
+
+#[derive(Clone, Default)]
+pub struct SignedVoluntaryExit {
+    pub message: VoluntaryExit,
+    pub signature: BLSSignature,
+}
+
+#[derive(Clone, Default)]
+pub struct BLSToExecutionChange {
+    pub validator_index: ValidatorIndex,
+    pub from_bls_pubkey: BLSPubkey,
+    pub to_execution_address: Vec<u8>,
+}
+
+#[derive(Clone, Default)]
+pub struct SignedBLSToExecutionChange {
+    pub message: BLSToExecutionChange,
+    pub signature: BLSSignature,
+}
+
 #[derive(Clone, Default)]
 pub struct BeaconBlockBody {
     pub randao_reveal: BLSSignature,
     pub eth1_data: Eth1Data,
     pub graffiti: Bytes32,
-    pub execution_payload: ExecutionPayload,
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing>,
+    pub attestations: Vec<Attestation>,
+    pub deposits: Vec<Deposit>,
+    pub voluntary_exits: Vec<SignedVoluntaryExit>,
     pub sync_aggregate: SyncAggregate,
+    pub execution_payload: ExecutionPayload,
+    pub bls_to_execution_changes: Vec<SignedBLSToExecutionChange>,
 }
 
 #[derive(Clone, Default)]
@@ -212,15 +303,15 @@ pub struct BeaconState {
 // ── Serialization helpers ────────────────────────
 
 fn read_u64(data: &[u8], off: usize) -> Option<(u64, usize)> {
-    if off + 8 > data.len() { return None; }
-    let v = u64::from_le_bytes(data[off..off+8].try_into().ok()?);
-    Some((v, off + 8))
+    let end = off.checked_add(8)?;
+    let v = u64::from_le_bytes(data.get(off..end)?.try_into().ok()?);
+    Some((v, end))
 }
 
 fn read_u32(data: &[u8], off: usize) -> Option<(u32, usize)> {
-    if off + 4 > data.len() { return None; }
-    let v = u32::from_le_bytes(data[off..off+4].try_into().ok()?);
-    Some((v, off + 4))
+    let end = off.checked_add(4)?;
+    let v = u32::from_le_bytes(data.get(off..end)?.try_into().ok()?);
+    Some((v, end))
 }
 
 fn read_u8(data: &[u8], off: usize) -> Option<(u8, usize)> {
@@ -235,14 +326,17 @@ fn read_bool(data: &[u8], off: usize) -> Option<(bool, usize)> {
 
 fn read_bytes(data: &[u8], off: usize) -> Option<(Vec<u8>, usize)> {
     let (len, off) = read_u32(data, off)?;
-    let n = len as usize;
-    if off + n > data.len() { return None; }
-    Some((data[off..off+n].to_vec(), off + n))
+    let end = off.checked_add(len as usize)?;
+    Some((data.get(off..end)?.to_vec(), end))
 }
 
-fn read_array<T>(data: &[u8], off: usize, reader: fn(&[u8], usize) -> Option<(T, usize)>) -> Option<(Vec<T>, usize)> {
+// Decodes one value at an offset, returning it and the offset past it
+type Reader<T> = fn(&[u8], usize) -> Option<(T, usize)>;
+
+fn read_array<T>(data: &[u8], off: usize, reader: Reader<T>) -> Option<(Vec<T>, usize)> {
     let (count, mut off) = read_u32(data, off)?;
-    let mut arr = Vec::with_capacity(count as usize);
+    // The count comes from the input; every item takes at least one byte
+    let mut arr = Vec::with_capacity((count as usize).min(data.len().saturating_sub(off)));
     for _ in 0..count {
         let (item, new_off) = reader(data, off)?;
         arr.push(item);
@@ -339,6 +433,141 @@ fn read_historical_summary(data: &[u8], off: usize) -> Option<(HistoricalSummary
     Some((HistoricalSummary { block_summary_root: bsr, state_summary_root: ssr }, off))
 }
 
+// ── Deserialize helpers for block operations ─────
+
+fn read_signed_block_header(data: &[u8], off: usize) -> Option<(SignedBeaconBlockHeader, usize)> {
+    let (msg, off) = read_block_header(data, off)?;
+    let (sig, off) = read_bytes(data, off)?;
+    Some((SignedBeaconBlockHeader { message: msg, signature: sig }, off))
+}
+
+fn read_proposer_slashing(data: &[u8], off: usize) -> Option<(ProposerSlashing, usize)> {
+    let (h1, off) = read_signed_block_header(data, off)?;
+    let (h2, off) = read_signed_block_header(data, off)?;
+    Some((ProposerSlashing { signed_header_1: h1, signed_header_2: h2 }, off))
+}
+
+fn read_attestation_data(data: &[u8], off: usize) -> Option<(AttestationData, usize)> {
+    let (sl, off) = read_u64(data, off)?;
+    let (ix, off) = read_u64(data, off)?;
+    let (bbr, off) = read_bytes(data, off)?;
+    let (src, off) = read_checkpoint(data, off)?;
+    let (tgt, off) = read_checkpoint(data, off)?;
+    Some((AttestationData { slot: sl, index: ix, beacon_block_root: bbr, source: src, target: tgt }, off))
+}
+
+fn read_indexed_attestation(data: &[u8], off: usize) -> Option<(IndexedAttestation, usize)> {
+    let (ai, off) = read_array(data, off, read_u64)?;
+    let (ad, off) = read_attestation_data(data, off)?;
+    let (sig, off) = read_bytes(data, off)?;
+    Some((IndexedAttestation { attesting_indices: ai, data: ad, signature: sig }, off))
+}
+
+fn read_attester_slashing(data: &[u8], off: usize) -> Option<(AttesterSlashing, usize)> {
+    let (a1, off) = read_indexed_attestation(data, off)?;
+    let (a2, off) = read_indexed_attestation(data, off)?;
+    Some((AttesterSlashing { attestation_1: a1, attestation_2: a2 }, off))
+}
+
+fn read_attestation(data: &[u8], off: usize) -> Option<(Attestation, usize)> {
+    let (ab, off) = read_bytes(data, off)?;
+    let (ad, off) = read_attestation_data(data, off)?;
+    let (sig, off) = read_bytes(data, off)?;
+    Some((Attestation { aggregation_bits: ab, data: ad, signature: sig }, off))
+}
+
+fn read_deposit_data(data: &[u8], off: usize) -> Option<(DepositData, usize)> {
+    let (pk, off) = read_bytes(data, off)?;
+    let (wc, off) = read_bytes(data, off)?;
+    let (amt, off) = read_u64(data, off)?;
+    let (sig, off) = read_bytes(data, off)?;
+    Some((DepositData { pubkey: pk, withdrawal_credentials: wc, amount: amt, signature: sig }, off))
+}
+
+fn read_deposit(data: &[u8], off: usize) -> Option<(Deposit, usize)> {
+    let (pf, off) = read_array(data, off, read_bytes)?;
+    let (dd, off) = read_deposit_data(data, off)?;
+    Some((Deposit { proof: pf, data: dd }, off))
+}
+
+fn read_signed_voluntary_exit(data: &[u8], off: usize) -> Option<(SignedVoluntaryExit, usize)> {
+    let (ep, off) = read_u64(data, off)?;
+    let (vi, off) = read_u64(data, off)?;
+    let (sig, off) = read_bytes(data, off)?;
+    Some((SignedVoluntaryExit { message: VoluntaryExit { epoch: ep, validator_index: vi }, signature: sig }, off))
+}
+
+fn read_signed_bls_to_execution_change(data: &[u8], off: usize) -> Option<(SignedBLSToExecutionChange, usize)> {
+    let (vi, off) = read_u64(data, off)?;
+    let (pk, off) = read_bytes(data, off)?;
+    let (addr, off) = read_bytes(data, off)?;
+    let (sig, off) = read_bytes(data, off)?;
+    Some((SignedBLSToExecutionChange {
+        message: BLSToExecutionChange { validator_index: vi, from_bls_pubkey: pk, to_execution_address: addr },
+        signature: sig,
+    }, off))
+}
+
+fn read_sync_aggregate(data: &[u8], off: usize) -> Option<(SyncAggregate, usize)> {
+    let (bits, off) = read_bytes(data, off)?;
+    let (sig, off) = read_bytes(data, off)?;
+    Some((SyncAggregate { sync_committee_bits: bits, sync_committee_signature: sig }, off))
+}
+
+fn read_withdrawal(data: &[u8], off: usize) -> Option<(Withdrawal, usize)> {
+    let (ix, off) = read_u64(data, off)?;
+    let (vi, off) = read_u64(data, off)?;
+    let (addr, off) = read_bytes(data, off)?;
+    let (amt, off) = read_u64(data, off)?;
+    Some((Withdrawal { index: ix, validator_index: vi, address: addr, amount: amt }, off))
+}
+
+fn read_execution_payload(data: &[u8], off: usize) -> Option<(ExecutionPayload, usize)> {
+    let (ph, off) = read_bytes(data, off)?;
+    let (fr, off) = read_bytes(data, off)?;
+    let (sr, off) = read_bytes(data, off)?;
+    let (rr, off) = read_bytes(data, off)?;
+    let (lb, off) = read_bytes(data, off)?;
+    let (pr, off) = read_bytes(data, off)?;
+    let (bn, off) = read_u64(data, off)?;
+    let (gl, off) = read_u64(data, off)?;
+    let (gu, off) = read_u64(data, off)?;
+    let (ts, off) = read_u64(data, off)?;
+    let (ed, off) = read_bytes(data, off)?;
+    let (bf, off) = read_u64(data, off)?;
+    let (bh, off) = read_bytes(data, off)?;
+    let (txs, off) = read_array(data, off, read_bytes)?;
+    let (ws, off) = read_array(data, off, read_withdrawal)?;
+    Some((ExecutionPayload {
+        parent_hash: ph, fee_recipient: fr, state_root: sr, receipts_root: rr,
+        logs_bloom: lb, prev_randao: pr, block_number: bn, gas_limit: gl,
+        gas_used: gu, timestamp: ts, extra_data: ed, base_fee_per_gas: bf,
+        block_hash: bh, transactions: txs, withdrawals: ws,
+    }, off))
+}
+
+fn read_block_body(data: &[u8], off: usize) -> Option<(BeaconBlockBody, usize)> {
+    let (rr, off) = read_bytes(data, off)?;
+    let (e1d, off) = read_eth1_data(data, off)?;
+    let (gr, off) = read_bytes(data, off)?;
+    // Operations
+    let (ps, off) = read_array(data, off, read_proposer_slashing)?;
+    let (asl, off) = read_array(data, off, read_attester_slashing)?;
+    let (atts, off) = read_array(data, off, read_attestation)?;
+    let (deps, off) = read_array(data, off, read_deposit)?;
+    let (exits, off) = read_array(data, off, read_signed_voluntary_exit)?;
+    // Altair / Bellatrix / Capella
+    let (sa, off) = read_sync_aggregate(data, off)?;
+    let (ep, off) = read_execution_payload(data, off)?;
+    let (btec, off) = read_array(data, off, read_signed_bls_to_execution_change)?;
+    Some((BeaconBlockBody {
+        randao_reveal: rr, eth1_data: e1d, graffiti: gr,
+        proposer_slashings: ps, attester_slashings: asl, attestations: atts,
+        deposits: deps, voluntary_exits: exits,
+        sync_aggregate: sa, execution_payload: ep, bls_to_execution_changes: btec,
+    }, off))
+}
+
 impl BeaconState {
     pub fn deserialize(data: &[u8]) -> Option<(Self, usize)> {
         let off = 0;
@@ -497,21 +726,35 @@ impl SignedBeaconBlock {
         let (proposer_index, off) = read_u64(data, off)?;
         let (parent_root, off) = read_bytes(data, off)?;
         let (state_root, off) = read_bytes(data, off)?;
-        let (randao_reveal, off) = read_bytes(data, off)?;
-        let (eth1_data, off) = read_eth1_data(data, off)?;
-        let (graffiti, off) = read_bytes(data, off)?;
-        // Simplified: skip operation count, use empty body
-        let (_op_count, off) = read_u32(data, off)?;
+        let (body, off) = read_block_body(data, off)?;
         let (signature, off) = read_bytes(data, off)?;
         Some((SignedBeaconBlock {
-            message: BeaconBlock {
-                slot, proposer_index, parent_root, state_root,
-                body: BeaconBlockBody {
-                    randao_reveal, eth1_data, graffiti,
-                    ..Default::default()
-                },
-            },
+            message: BeaconBlock { slot, proposer_index, parent_root, state_root, body },
             signature,
         }, off))
     }
+    /// Block chain as a u32 count followed by the blocks
+    pub fn deserialize_list(data: &[u8]) -> Option<(Vec<Self>, usize)> {
+        read_array(data, 0, |data, off| {
+            let (block, len) = SignedBeaconBlock::deserialize(data.get(off..)?)?;
+            Some((block, off + len))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_oversized_length_prefixes() {
+        // A block count far beyond the input must fail, not reserve memory
+        assert!(SignedBeaconBlock::deserialize_list(&u32::MAX.to_le_bytes()).is_none());
+        let mut data = u32::MAX.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0; 8]);
+        assert!(read_bytes(&data, 0).is_none());
+        assert!(read_bytes(&data, usize::MAX - 2).is_none());
+        assert!(read_u64(&data, usize::MAX - 4).is_none());
+        assert!(read_u32(&data, usize::MAX - 2).is_none());
+    }
 }
@@ -0,0 +1,238 @@
+//! Heap snapshot of the Lean init guest, taken at build time.
+//!
+//! `guest-eth2-init-snapshot` is the init guest built with its `heap-snapshot` feature. A
+//! second build adds `heap-snapshot-capture`: given [`HEAP_SNAPSHOT_REQUEST`] and the writable
+//! segments of its ELF as input, it runs Lean initialization and writes those segments'
+//! memory to [`HEAP_SNAPSHOT_FD`]. The two builds differ only in one flag byte, checked here,
+//! so the memory replaces the segments' initial contents in a copy of the first build, which
+//! then starts with Lean initialized: closed terms, module flags and the sbrk heap they live
+//! in. The capture build is only ever run here; the proven ELF cannot answer the request.
+//!
+//! Rust statics keep their initial values. The zkVM runtime sets its own up on every start
+//! (and panics if they already are), and Rust's heap, above the segments, is not captured.
+
+use anyhow::{bail, ensure, Context, Result};
+use elf::abi::{PF_W, PT_LOAD, STT_OBJECT};
+use elf::endian::LittleEndian;
+use elf::ElfBytes;
+use eth2_journal::{HEAP_SNAPSHOT_FD, HEAP_SNAPSHOT_REQUEST};
+use risc0_binfmt::ProgramBinary;
+use risc0_build::{GuestListEntry, GuestOptionsBuilder};
+use risc0_zkvm::{default_executor, ExecutorEnv};
+use std::fmt::Write;
+use std::path::Path;
+use std::{env, fs};
+
+/// The guest's feature that answers the capture request
+const CAPTURE_FEATURE: &str = "heap-snapshot-capture";
+
+/// Set in the capture build only (guest-eth2-init's main.rs)
+const CAPTURE_FLAG: &str = "LEAN_ETH2_SNAPSHOT_CAPTURE";
+
+/// Write `snapshot.rs` to `OUT_DIR`, defining `<NAME>_ELF` and `<NAME>_ID` for the snapshot
+/// of `guest`, whose package is the methods directory of the same name. Without a snapshot
+/// (guest build skipped, or it failed with a warning) they are empty, or `guest` itself, as
+/// for any other method, and `<NAME>_APPLIED` is false.
+pub fn embed(name: &str, guest: &GuestListEntry) {
+    let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR is set for build scripts");
+    let mut code = String::new();
+    if guest.elf.is_empty() {
+        // RISC0_SKIP_BUILD
+        writeln!(code, "pub const {}_ELF: &[u8] = &[];", name).unwrap();
+        writeln!(code, "pub const {}_ID: [u32; 8] = [0; 8];", name).unwrap();
+        writeln!(code, "pub const {}_APPLIED: bool = false;", name).unwrap();
+    } else {
+        let snapshot = build_capture_elf(guest).and_then(|capture| snapshot(&guest.elf, &capture));
+        let (elf, image_id, applied) = match snapshot {
+            Ok(elf) => {
+                let image_id = risc0_binfmt::compute_image_id(&elf).expect("snapshot ELF decodes");
+                (elf, image_id, true)
+            }
+            Err(e) => {
+                println!("cargo:warning=heap snapshot of {} failed, using it as is: {:#}", guest.name, e);
+                (guest.elf.to_vec(), guest.image_id, false)
+            }
+        };
+        let path = Path::new(&out_dir).join(format!("{}.bin", guest.name));
+        fs::write(&path, elf).expect("writing snapshot ELF");
+        writeln!(code, "pub const {}_ELF: &[u8] = include_bytes!({:?});", name, path).unwrap();
+        writeln!(code, "pub const {}_ID: [u32; 8] = {:?};", name, image_id.as_words()).unwrap();
+        writeln!(code, "pub const {}_APPLIED: bool = {};", name, applied).unwrap();
+    }
+    fs::write(Path::new(&out_dir).join("snapshot.rs"), code).expect("writing snapshot.rs");
+}
+
+/// Build `guest`'s package again with the capture feature, into its own target directory
+fn build_capture_elf(guest: &GuestListEntry) -> Result<Vec<u8>> {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").context("CARGO_MANIFEST_DIR is unset")?;
+    let out_dir = env::var_os("OUT_DIR").context("OUT_DIR is unset")?;
+    let package = risc0_build::get_package(Path::new(&manifest_dir).join(guest.name.as_ref()));
+    let options = GuestOptionsBuilder::default()
+        .features(vec![CAPTURE_FEATURE.to_string()])
+        .build()?;
+    let target_dir = Path::new(&out_dir).join(format!("{}-capture", guest.name));
+    risc0_build::build_package(&package, target_dir, options)?
+        .into_iter()
+        .find(|built| built.name == guest.name)
+        .map(|built| built.elf.to_vec())
+        .context("the capture build has no guest binary")
+}
+
+/// Run `capture_elf` up to the end of Lean initialization and return `elf` with the captured
+/// memory
+fn snapshot(elf: &[u8], capture_elf: &[u8]) -> Result<Vec<u8>> {
+    let binary = ProgramBinary::decode(elf)?;
+    let user_elf = binary.user_elf;
+    let file = ElfBytes::<LittleEndian>::minimal_parse(user_elf).context("parsing user ELF")?;
+    check_same_image(&file, user_elf, ProgramBinary::decode(capture_elf)?.user_elf)?;
+    let writable: Vec<(usize, elf::segment::ProgramHeader)> = file
+        .segments()
+        .context("user ELF has no program headers")?
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.p_type == PT_LOAD && p.p_flags & PF_W != 0)
+        .collect();
+
+    let mut request = HEAP_SNAPSHOT_REQUEST.to_vec();
+    for (_, p) in &writable {
+        request.extend_from_slice(&(p.p_vaddr as u32).to_le_bytes());
+        request.extend_from_slice(&(p.p_memsz as u32).to_le_bytes());
+    }
+    let mut records = Vec::new();
+    let env = ExecutorEnv::builder()
+        .write(&request)?
+        .write_fd(HEAP_SNAPSHOT_FD, &mut records)
+        .build()?;
+    default_executor().execute(env, capture_elf).context("executing the capture run")?;
+    ensure!(!records.is_empty(), "the guest captured nothing; is {} enabled?", CAPTURE_FEATURE);
+
+    // Initial contents of each writable segment, then the captured memory over them
+    let initial: Vec<Vec<u8>> = writable
+        .iter()
+        .map(|(_, p)| {
+            let mut image = user_elf[p.p_offset as usize..][..p.p_filesz as usize].to_vec();
+            image.resize(p.p_memsz as usize, 0);
+            image
+        })
+        .collect();
+    let mut images = initial.clone();
+    let mut rest = &records[..];
+    while !rest.is_empty() {
+        let (header, tail) = rest.split_at_checked(8).context("truncated snapshot record")?;
+        let addr = u32::from_le_bytes(header[..4].try_into()?) as u64;
+        let len = u32::from_le_bytes(header[4..].try_into()?) as usize;
+        let (bytes, tail) = tail.split_at_checked(len).context("truncated snapshot record")?;
+        let Some(i) = writable
+            .iter()
+            .position(|(_, p)| addr >= p.p_vaddr && addr + len as u64 <= p.p_vaddr + p.p_memsz)
+        else {
+            bail!("snapshot record at {:#x} is outside the writable segments", addr);
+        };
+        let start = (addr - writable[i].1.p_vaddr) as usize;
+        images[i][start..start + len].copy_from_slice(bytes);
+        rest = tail;
+    }
+
+    let (symtab, strtab) = file.symbol_table()?.context("user ELF has no symbol table")?;
+    let mut rust_statics = 0;
+    for sym in symtab.iter().filter(|s| s.st_symtype() == STT_OBJECT && s.st_size > 0) {
+        if !is_rust_symbol(strtab.get(sym.st_name as usize)?) {
+            continue;
+        }
+        let (start, end) = (sym.st_value, sym.st_value + sym.st_size);
+        for (i, (_, p)) in writable.iter().enumerate() {
+            if start >= p.p_vaddr && end <= p.p_vaddr + p.p_memsz {
+                let range = (start - p.p_vaddr) as usize..(end - p.p_vaddr) as usize;
+                images[i][range.clone()].copy_from_slice(&initial[i][range]);
+                rust_statics += 1;
+            }
+        }
+    }
+    // Without them the snapshot would restore the runtime's statics and panic on start
+    ensure!(rust_statics > 0, "no Rust statics in the symbol table; is the guest stripped?");
+
+    // Append each segment's new contents, trailing zeros left to p_memsz, and point its
+    // program header at them
+    let mut user = user_elf.to_vec();
+    let phoff = file.ehdr.e_phoff as usize;
+    let phentsize = file.ehdr.e_phentsize as usize;
+    for ((index, p), image) in writable.iter().zip(&images) {
+        let filesz = image.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        // File offsets stay congruent to addresses modulo the alignment, as the ELF spec asks
+        let align = p.p_align.max(1) as usize;
+        let padding = (p.p_vaddr as usize % align + align - user.len() % align) % align;
+        user.resize(user.len() + padding, 0);
+        let header = phoff + index * phentsize;
+        let offset = user.len() as u32;
+        user[header + 4..header + 8].copy_from_slice(&offset.to_le_bytes());
+        user[header + 16..header + 20].copy_from_slice(&(filesz as u32).to_le_bytes());
+        user.extend_from_slice(&image[..filesz]);
+    }
+
+    let mut snapshot = ProgramBinary::new(&user, binary.kernel_elf);
+    snapshot.header = binary.header;
+    Ok(snapshot.encode())
+}
+
+/// Memory captured from one build only fits the other if every loaded byte but the capture
+/// flag is the same in both
+fn check_same_image(
+    file: &ElfBytes<LittleEndian>,
+    user_elf: &[u8],
+    capture_elf: &[u8],
+) -> Result<()> {
+    let capture =
+        ElfBytes::<LittleEndian>::minimal_parse(capture_elf).context("parsing capture ELF")?;
+    let (symtab, strtab) = file.symbol_table()?.context("user ELF has no symbol table")?;
+    let mut flag = None;
+    for sym in symtab.iter() {
+        if strtab.get(sym.st_name as usize)? == CAPTURE_FLAG {
+            flag = Some(sym.st_value..sym.st_value + sym.st_size);
+        }
+    }
+    let flag = flag.with_context(|| format!("user ELF has no {} symbol", CAPTURE_FLAG))?;
+
+    let loads = |file: &ElfBytes<LittleEndian>| -> Result<Vec<elf::segment::ProgramHeader>> {
+        Ok(file
+            .segments()
+            .context("ELF has no program headers")?
+            .iter()
+            .filter(|p| p.p_type == PT_LOAD)
+            .collect())
+    };
+    let layout = |p: &elf::segment::ProgramHeader| (p.p_vaddr, p.p_memsz, p.p_filesz, p.p_flags);
+    let (ours, theirs) = (loads(file)?, loads(&capture)?);
+    ensure!(ours.len() == theirs.len(), "the capture build has a different number of segments");
+    for (p, q) in ours.iter().zip(&theirs) {
+        ensure!(
+            layout(p) == layout(q),
+            "the capture build's segment at {:#x} differs in layout",
+            p.p_vaddr
+        );
+        let mut a = user_elf[p.p_offset as usize..][..p.p_filesz as usize].to_vec();
+        let mut b = capture_elf[q.p_offset as usize..][..q.p_filesz as usize].to_vec();
+        let loaded = p.p_vaddr..p.p_vaddr + p.p_filesz;
+        for addr in flag.clone().filter(|addr| loaded.contains(addr)) {
+            let i = (addr - p.p_vaddr) as usize;
+            (a[i], b[i]) = (0, 0);
+        }
+        ensure!(a == b, "the capture build's segment at {:#x} differs in contents", p.p_vaddr);
+    }
+    Ok(())
+}
+
+/// Rust v0 or legacy mangling; the latter ends in a `17h<16 hex digits>E` hash, which C++
+/// names (the Lean runtime's) do not
+fn is_rust_symbol(name: &str) -> bool {
+    if name.starts_with("_R") {
+        return true;
+    }
+    let Some(hash) = name
+        .strip_prefix("_ZN")
+        .and_then(|rest| rest.strip_suffix('E'))
+        .and_then(|rest| rest.get(rest.len().saturating_sub(19)..))
+    else {
+        return false;
+    };
+    hash.len() == 19 && hash.starts_with("17h") && hash[3..].bytes().all(|b| b.is_ascii_hexdigit())
+}
@@ -1 +1,2 @@
 include!(concat!(env!("OUT_DIR"), "/methods.rs"));
+include!(concat!(env!("OUT_DIR"), "/snapshot.rs"));
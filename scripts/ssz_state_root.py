@@ -0,0 +1,177 @@
+#!/usr/bin/env python3
+"""hash_tree_root of BeaconStates in the guests' binary serialization.
+
+A separate merkleization written from the Capella SSZ spec, sharing no code with
+methods/guest-rust-eth2/src/ssz.rs; it derives the known-answer roots the benchmark's
+test data is checked against. Vectors the test states store short (block/state roots,
+randao mixes, slashings, sync committee pubkeys) are padded with zeros to their spec
+length, and only the first byte of justification_bits is the Bitvector[4].
+
+Usage: ssz_state_root.py STATE_FILE...
+"""
+import hashlib
+import struct
+import sys
+
+SLOTS_PER_HISTORICAL_ROOT = 8192
+HISTORICAL_ROOTS_LIMIT = 2**24
+ETH1_VOTES_LIMIT = 64 * 32
+VALIDATOR_REGISTRY_LIMIT = 2**40
+EPOCHS_PER_HISTORICAL_VECTOR = 65536
+EPOCHS_PER_SLASHINGS_VECTOR = 8192
+SYNC_COMMITTEE_SIZE = 512
+
+
+def sha256(a, b):
+    return hashlib.sha256(a + b).digest()
+
+
+ZERO_HASHES = [bytes(32)]
+for _ in range(64):
+    ZERO_HASHES.append(sha256(ZERO_HASHES[-1], ZERO_HASHES[-1]))
+
+
+def merkleize(chunks, limit=None):
+    limit = len(chunks) if limit is None else limit
+    assert len(chunks) <= limit
+    depth = max(limit - 1, 0).bit_length()
+    layer = list(chunks)
+    if not layer:
+        return ZERO_HASHES[depth]
+    for d in range(depth):
+        if len(layer) % 2:
+            layer.append(ZERO_HASHES[d])
+        layer = [sha256(layer[i], layer[i + 1]) for i in range(0, len(layer), 2)]
+    return layer[0]
+
+
+def pack(data):
+    data = data + bytes(-len(data) % 32)
+    return [data[i:i + 32] for i in range(0, len(data), 32)]
+
+
+def mix_in_length(root, length):
+    return sha256(root, struct.pack("<Q", length) + bytes(24))
+
+
+def uint64(v):
+    return struct.pack("<Q", v) + bytes(24)
+
+
+def boolean(v):
+    return bytes([v != 0]) + bytes(31)
+
+
+def byte_vector(data, n):
+    assert len(data) == n, (len(data), n)
+    return merkleize(pack(data), (n + 31) // 32)
+
+
+def byte_list(data, limit):
+    return mix_in_length(merkleize(pack(data), (limit + 31) // 32), len(data))
+
+
+def uint64_packed(values, limit):
+    return merkleize(pack(b"".join(struct.pack("<Q", v) for v in values)), limit * 8 // 32)
+
+
+def padded(items, n, zero):
+    assert len(items) <= n, (len(items), n)
+    return items + [zero] * (n - len(items))
+
+
+class Reader:
+    def __init__(self, data):
+        self.data = data
+        self.pos = 0
+
+    def u64(self):
+        (v,) = struct.unpack_from("<Q", self.data, self.pos)
+        self.pos += 8
+        return v
+
+    def u32(self):
+        (v,) = struct.unpack_from("<I", self.data, self.pos)
+        self.pos += 4
+        return v
+
+    def u8(self):
+        self.pos += 1
+        return self.data[self.pos - 1]
+
+    def bytes(self):
+        n = self.u32()
+        self.pos += n
+        return bytes(self.data[self.pos - n:self.pos])
+
+    def list(self, item):
+        return [item() for _ in range(self.u32())]
+
+
+def state_root(data):
+    r = Reader(data)
+    root32 = lambda: byte_vector(r.bytes(), 32)
+    checkpoint = lambda: merkleize([uint64(r.u64()), root32()])
+    eth1_data = lambda: merkleize([root32(), uint64(r.u64()), root32()])
+
+    def roots_vector(n):
+        return merkleize([byte_vector(x, 32) for x in padded(r.list(r.bytes), n, bytes(32))], n)
+
+    def validator():
+        return merkleize([
+            byte_vector(r.bytes(), 48), root32(), uint64(r.u64()), boolean(r.u8()),
+            uint64(r.u64()), uint64(r.u64()), uint64(r.u64()), uint64(r.u64()),
+        ])
+
+    def uint64_list():
+        values = r.list(r.u64)
+        return mix_in_length(uint64_packed(values, VALIDATOR_REGISTRY_LIMIT), len(values))
+
+    def sync_committee():
+        pubkeys = padded(r.list(r.bytes), SYNC_COMMITTEE_SIZE, bytes(48))
+        return merkleize([
+            merkleize([byte_vector(p, 48) for p in pubkeys], SYNC_COMMITTEE_SIZE),
+            byte_vector(r.bytes(), 48),
+        ])
+
+    fields = [uint64(r.u64()), root32(), uint64(r.u64())]
+    fields.append(merkleize([byte_vector(r.bytes(), 4), byte_vector(r.bytes(), 4), uint64(r.u64())]))
+    fields.append(merkleize([uint64(r.u64()), uint64(r.u64()), root32(), root32(), root32()]))
+    fields.append(roots_vector(SLOTS_PER_HISTORICAL_ROOT))
+    fields.append(roots_vector(SLOTS_PER_HISTORICAL_ROOT))
+    historical_roots = [byte_vector(x, 32) for x in r.list(r.bytes)]
+    fields.append(mix_in_length(merkleize(historical_roots, HISTORICAL_ROOTS_LIMIT), len(historical_roots)))
+    fields.append(eth1_data())
+    votes = r.list(eth1_data)
+    fields.append(mix_in_length(merkleize(votes, ETH1_VOTES_LIMIT), len(votes)))
+    fields.append(uint64(r.u64()))
+    validators = r.list(validator)
+    fields.append(mix_in_length(merkleize(validators, VALIDATOR_REGISTRY_LIMIT), len(validators)))
+    fields.append(uint64_list())
+    fields.append(roots_vector(EPOCHS_PER_HISTORICAL_VECTOR))
+    slashings = padded(r.list(r.u64), EPOCHS_PER_SLASHINGS_VECTOR, 0)
+    fields.append(uint64_packed(slashings, EPOCHS_PER_SLASHINGS_VECTOR))
+    fields.append(byte_list(bytes(r.list(r.u8)), VALIDATOR_REGISTRY_LIMIT))
+    fields.append(byte_list(bytes(r.list(r.u8)), VALIDATOR_REGISTRY_LIMIT))
+    justification_bits = r.bytes()
+    assert not any(justification_bits[1:])
+    fields.append(byte_vector(justification_bits[:1], 1))
+    fields += [checkpoint(), checkpoint(), checkpoint()]
+    fields.append(uint64_list())
+    fields += [sync_committee(), sync_committee()]
+    fields.append(merkleize([
+        root32(), byte_vector(r.bytes(), 20), root32(), root32(), byte_vector(r.bytes(), 256), root32(),
+        uint64(r.u64()), uint64(r.u64()), uint64(r.u64()), uint64(r.u64()), byte_list(r.bytes(), 32),
+        uint64(r.u64()), root32(), root32(), root32(),
+    ]))
+    fields += [uint64(r.u64()), uint64(r.u64())]
+    summaries = r.list(lambda: merkleize([root32(), root32()]))
+    fields.append(mix_in_length(merkleize(summaries, HISTORICAL_ROOTS_LIMIT), len(summaries)))
+    assert r.pos == len(data), "trailing bytes after the state"
+    return merkleize(fields)
+
+
+if __name__ == "__main__":
+    for path in sys.argv[1:]:
+        with open(path, "rb") as f:
+            print(state_root(f.read()).hex(), path)